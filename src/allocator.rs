@@ -1,51 +1,120 @@
 use std::alloc::{GlobalAlloc, Layout, System};
 use std::sync::atomic::{AtomicUsize, Ordering};
 
-pub struct LimitedAllocator {
-    limit: AtomicUsize,
-    allocated: AtomicUsize,
+// provide a sensible default; can be overwritten by calling set_limit
+const DEFAULT_LIMIT: usize = 2 * 1024 * 1024 * 1024;
+
+// The accounting is kept in statics rather than in the allocator instance, so
+// that the resource limiter can read it regardless of whether the allocator
+// was installed by this crate (the `global-allocator` feature) or by the
+// embedding binary. The counters do not synchronize any other memory, so
+// relaxed ordering is sufficient.
+static LIMIT: AtomicUsize = AtomicUsize::new(DEFAULT_LIMIT);
+static ALLOCATED: AtomicUsize = AtomicUsize::new(0);
+
+/// An allocator that tracks the number of allocated bytes and fails
+/// allocations beyond a limit, by wrapping another allocator (`System` by
+/// default). The memory limit of a `ResourceLimiter` is only enforced if a
+/// `LimitedAllocator` is installed as the global allocator.
+///
+/// With the (default) `global-allocator` feature, this crate installs one
+/// itself. Binaries that want to keep control of their allocator can disable
+/// the feature and opt into memory limiting explicitly, optionally wrapping
+/// their allocator of choice:
+///
+/// ```ignore
+/// #[global_allocator]
+/// static ALLOCATOR: LimitedAllocator<Jemalloc> = LimitedAllocator::new(Jemalloc);
+/// ```
+pub struct LimitedAllocator<A = System> {
+    inner: A,
+}
+
+// Counts `size` more allocated bytes, unless that exceeds the limit.
+fn reserve(size: usize) -> bool {
+    let old_size = ALLOCATED.fetch_add(size, Ordering::Relaxed);
+    if old_size.saturating_add(size) > LIMIT.load(Ordering::Relaxed) {
+        ALLOCATED.fetch_sub(size, Ordering::Relaxed);
+        false
+    } else {
+        true
+    }
 }
 
-unsafe impl GlobalAlloc for LimitedAllocator {
+fn release(size: usize) {
+    ALLOCATED.fetch_sub(size, Ordering::Relaxed);
+}
+
+// `alloc_zeroed` and `realloc` are forwarded as well, so that the wrapped
+// allocator's zeroed and in-place allocations are kept.
+unsafe impl<A: GlobalAlloc> GlobalAlloc for LimitedAllocator<A> {
     unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
-        let old_size = self.allocated.fetch_add(layout.size(), Ordering::SeqCst);
-        if old_size.saturating_add(layout.size()) > self.limit.load(Ordering::SeqCst) {
-            self.allocated.fetch_sub(layout.size(), Ordering::SeqCst);
-            std::ptr::null_mut()
-        } else {
-            let ptr = System.alloc(layout);
-            if ptr.is_null() {
-                self.allocated.fetch_sub(layout.size(), Ordering::SeqCst);
-            }
-            ptr
+        if !reserve(layout.size()) {
+            return std::ptr::null_mut();
+        }
+        let ptr = self.inner.alloc(layout);
+        if ptr.is_null() {
+            release(layout.size());
         }
+        ptr
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        if !reserve(layout.size()) {
+            return std::ptr::null_mut();
+        }
+        let ptr = self.inner.alloc_zeroed(layout);
+        if ptr.is_null() {
+            release(layout.size());
+        }
+        ptr
     }
 
     unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
-        self.allocated.fetch_sub(layout.size(), Ordering::SeqCst);
-        System.dealloc(ptr, layout);
+        release(layout.size());
+        self.inner.dealloc(ptr, layout);
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let old_size = layout.size();
+        // only growth is checked against the limit
+        if new_size > old_size && !reserve(new_size - old_size) {
+            return std::ptr::null_mut();
+        }
+        let new_ptr = self.inner.realloc(ptr, layout, new_size);
+        match (new_ptr.is_null(), new_size > old_size) {
+            (true, true) => release(new_size - old_size),
+            (false, false) => release(old_size - new_size),
+            _ => {}
+        }
+        new_ptr
     }
 }
 
-impl LimitedAllocator {
+impl<A> LimitedAllocator<A> {
+    pub const fn new(inner: A) -> Self {
+        Self { inner }
+    }
+
     pub fn set_limit(&self, bytes: usize) {
-        self.limit.store(bytes, Ordering::SeqCst);
+        set_memory_limit(bytes);
     }
 }
 
-// provide a sensible default; can be overwritten by calling set_limit
-const DEFAULT_LIMIT: usize = 2 * 1024 * 1024 * 1024;
-
+#[cfg(any(feature = "global-allocator", test))]
 #[global_allocator]
-static ALLOCATOR: LimitedAllocator = LimitedAllocator {
-    limit: AtomicUsize::new(DEFAULT_LIMIT),
-    allocated: AtomicUsize::new(0),
-};
+static ALLOCATOR: LimitedAllocator = LimitedAllocator::new(System);
 
 pub fn get_memory_usage() -> usize {
-    ALLOCATOR.allocated.load(Ordering::SeqCst)
+    ALLOCATED.load(Ordering::Relaxed)
 }
 
 pub fn set_memory_limit(bytes: usize) {
-    ALLOCATOR.set_limit(bytes);
+    LIMIT.store(bytes, Ordering::Relaxed);
+}
+
+// In practice a running program always holds some heap memory, so a zero count
+// means no `LimitedAllocator` is installed.
+pub fn is_installed() -> bool {
+    get_memory_usage() > 0
 }
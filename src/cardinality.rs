@@ -0,0 +1,46 @@
+use crate::{fbas::FbasError, proof::ClauseSink};
+use batsat::Lit;
+
+// Cardinality constraints are encoded with a sequential counter (Sinz, "Towards
+// an Optimal CNF Encoding of Boolean Cardinality Constraints", 2005). Only the
+// upward direction is encoded: a counter output is forced true once enough
+// inputs are true, but may be true spuriously. This is all that is needed to
+// bound the number of true inputs from above, by asserting (or assuming) an
+// output to be false. Building the outputs once and bounding them through
+// assumptions lets the same solver be queried for different bounds.
+
+/// Returns outputs where `outputs[j]` is implied by at least `j + 1` of
+/// `inputs` being true, for `j < max`. There are fewer than `max` outputs if
+/// there are fewer inputs.
+pub(crate) fn at_least<S: ClauseSink>(
+    sink: &mut S,
+    inputs: &[Lit],
+    max: usize,
+) -> Result<Vec<Lit>, FbasError> {
+    let mut registers: Vec<Lit> = vec![];
+    for x in inputs.iter() {
+        let width = (registers.len() + 1).min(max);
+        let next = (0..width)
+            .map(|_| Lit::new(sink.new_var(), true))
+            .collect::<Vec<_>>();
+        for (j, r) in next.iter().enumerate() {
+            // carry the count of the previous inputs
+            if let Some(prev) = registers.get(j) {
+                sink.add_clause(&mut vec![!*prev, *r])?;
+            }
+            // count this input
+            match j.checked_sub(1).map(|j| registers[j]) {
+                Some(prev) => sink.add_clause(&mut vec![!*x, !prev, *r])?,
+                None => sink.add_clause(&mut vec![!*x, *r])?,
+            }
+        }
+        registers = next;
+    }
+    Ok(registers)
+}
+
+/// Returns the assumption bounding the number of true inputs counted by
+/// `outputs` to at most `k`, if any is needed.
+pub(crate) fn at_most_assumption(outputs: &[Lit], k: usize) -> Option<Lit> {
+    outputs.get(k).map(|output| !*output)
+}
@@ -0,0 +1,328 @@
+use crate::{
+    cardinality::{at_least, at_most_assumption},
+    fbas::{Fbas, FbasError},
+    fbas_analyze::{solve_resumable, FbasAnalyzer, LimitedSolver, VarManager},
+    organizations::Organizations,
+    proof::{AnalyzerCallbacks, ClauseSink},
+    resource_limiter::{Phase, ResourceLimiter},
+};
+use batsat::{Lit, Solver};
+use log::trace;
+use petgraph::{graph::NodeIndex, Direction};
+use std::collections::{BTreeMap, BTreeSet};
+
+// Finds the smallest number of organizations whose faulty validators break
+// liveness (blocking) or safety (splitting). Every validator gets a variable
+// for being faulty, and every organization one for counting as faulty, which
+// is forced once enough of its validators are faulty. The number of faulty
+// organizations is bounded by a cardinality constraint, assumed for bounds
+// 0, 1, 2, ... until the first one that admits a solution.
+//
+// - Splitting: the faulty validators are Byzantine, and the split formula of
+//   the analyzer is relaxed for them (see `add_formula_clauses_with_faults`).
+//   A solution is a pair of quorums intersecting only in faulty validators.
+//
+// - Blocking: the faulty validators crash, and no quorum may remain among the
+//   others. This cannot be stated directly as a (polynomial) formula, so it is
+//   found by refinement: every candidate found by the solver is checked with
+//   the quorum fixpoint, and if a quorum remains, a clause requiring a faulty
+//   validator in (a minimal subset of) that quorum is added. Validators with
+//   the same quorum set that appear in the same quorum sets are
+//   interchangeable, so the clause is lifted to all such permutations of the
+//   quorum: some class of interchangeable validators must have too few
+//   correct validators left to host its part of the quorum.
+
+/// When an organization counts as faulty.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum OrganizationFaults {
+    /// As soon as any of its validators is faulty.
+    Any,
+    /// Once at least this fraction of its validators (rounded up, and at least
+    /// one) is faulty.
+    Fraction(f64),
+}
+
+impl OrganizationFaults {
+    fn threshold(&self, validators: usize) -> usize {
+        match self {
+            OrganizationFaults::Any => 1,
+            OrganizationFaults::Fraction(f) => {
+                ((f * validators as f64).ceil() as usize).clamp(1, validators.max(1))
+            }
+        }
+    }
+}
+
+/// A smallest set of faulty organizations, with an example of the faulty
+/// validators.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct OrganizationFaultSet {
+    pub organizations: Vec<String>,
+    /// May include validators of organizations that do not count as faulty
+    /// (see `OrganizationFaults::Fraction`).
+    pub validators: Vec<String>,
+}
+
+struct Organization {
+    name: String,
+    faulty: Lit,
+    validators: Vec<NodeIndex>,
+    // faulty validators for the organization to count as faulty
+    threshold: usize,
+}
+
+// faulty organizations and validators found by the solver
+type Candidate = (Vec<String>, BTreeSet<NodeIndex>);
+
+// the class of each validator, and a counter over each class
+type Classes = (BTreeMap<NodeIndex, usize>, Vec<Vec<Lit>>);
+
+struct FaultSearch<'a> {
+    fbas: &'a Fbas,
+    solver: Solver<AnalyzerCallbacks>,
+    faulty: BTreeMap<NodeIndex, Lit>,
+    organizations: Vec<Organization>,
+    // `at_least_faulty[k]`: at least k+1 organizations are faulty
+    at_least_faulty: Vec<Lit>,
+}
+
+impl<'a> FaultSearch<'a> {
+    fn new(
+        fbas: &'a Fbas,
+        organizations: &Organizations,
+        faults: OrganizationFaults,
+        limiter: ResourceLimiter,
+    ) -> Result<Self, FbasError> {
+        limiter.enter_phase(Phase::Formula);
+        let mut solver = Solver::new(Default::default(), AnalyzerCallbacks::new(limiter));
+        let mut sink = LimitedSolver(&mut solver);
+        let mut members: BTreeMap<&str, Vec<NodeIndex>> = BTreeMap::new();
+        let mut faulty = BTreeMap::new();
+        for ni in fbas.validators.iter() {
+            let validator = fbas.try_get_validator_string(ni)?;
+            let org = organizations
+                .organization_of(&validator)
+                .ok_or(FbasError::InternalError("validator without organization"))?;
+            members.entry(org).or_default().push(*ni);
+            faulty.insert(*ni, Lit::new(sink.new_var(), true));
+        }
+        let mut orgs = vec![];
+        for (name, validators) in members.into_iter() {
+            let org = Organization {
+                name: name.to_string(),
+                faulty: Lit::new(sink.new_var(), true),
+                threshold: faults.threshold(validators.len()),
+                validators,
+            };
+            let lits = org
+                .validators
+                .iter()
+                .map(|ni| faulty[ni])
+                .collect::<Vec<_>>();
+            if let Some(output) = at_least(&mut sink, &lits, org.threshold)?.get(org.threshold - 1)
+            {
+                sink.add_clause(&mut vec![!*output, org.faulty])?;
+            }
+            orgs.push(org);
+        }
+        let at_least_faulty = at_least(
+            &mut sink,
+            &orgs.iter().map(|org| org.faulty).collect::<Vec<_>>(),
+            orgs.len(),
+        )?;
+        // all solver runs of the search share one phase
+        solver.cb().limiter.enter_phase(Phase::Solve);
+        Ok(Self {
+            fbas,
+            solver,
+            faulty,
+            organizations: orgs,
+            at_least_faulty,
+        })
+    }
+
+    // Solves with at most `k` faulty organizations, returning the faulty
+    // organizations and validators.
+    fn solve_with_at_most(&mut self, k: usize) -> Result<Option<Candidate>, FbasError> {
+        let assumptions = at_most_assumption(&self.at_least_faulty, k)
+            .into_iter()
+            .collect::<Vec<_>>();
+        let (organizations, faulty) = (&self.organizations, &self.faulty);
+        solve_resumable(&mut self.solver, &assumptions, |value| {
+            let organizations = organizations
+                .iter()
+                .filter(|org| value(org.faulty))
+                .map(|org| org.name.clone())
+                .collect();
+            let validators = faulty
+                .iter()
+                .filter(|(_, f)| value(**f))
+                .map(|(ni, _)| *ni)
+                .collect();
+            (organizations, validators)
+        })
+    }
+
+    fn fault_set(
+        &self,
+        organizations: Vec<String>,
+        validators: &BTreeSet<NodeIndex>,
+    ) -> Result<OrganizationFaultSet, FbasError> {
+        Ok(OrganizationFaultSet {
+            organizations,
+            validators: validators
+                .iter()
+                .map(|ni| self.fbas.try_get_validator_string(ni))
+                .collect::<Result<_, _>>()?,
+        })
+    }
+
+    fn minimal_splitting_set(mut self) -> Result<Option<OrganizationFaultSet>, FbasError> {
+        let vars = VarManager::new(self.fbas, &mut LimitedSolver(&mut self.solver));
+        FbasAnalyzer::add_formula_clauses_with_faults(
+            self.fbas,
+            &vars,
+            &self.faulty,
+            &BTreeMap::new(),
+            &mut LimitedSolver(&mut self.solver),
+        )?;
+        for k in 0..=self.organizations.len() {
+            trace!(target: "SCP", "searching splitting sets of {} organizations", k);
+            if let Some((organizations, validators)) = self.solve_with_at_most(k)? {
+                return self.fault_set(organizations, &validators).map(Some);
+            }
+        }
+        Ok(None)
+    }
+
+    // Adds the validators that can crash without changing the faulty
+    // organizations: all validators of faulty organizations, and up to the
+    // threshold of the others. The more validators are crashed, the more the
+    // quorum found by the fixpoint check (if any) excludes.
+    fn saturate(
+        &self,
+        organizations: &[String],
+        mut validators: BTreeSet<NodeIndex>,
+    ) -> BTreeSet<NodeIndex> {
+        for org in self.organizations.iter() {
+            if organizations.contains(&org.name) {
+                validators.extend(org.validators.iter());
+                continue;
+            }
+            let mut count = org
+                .validators
+                .iter()
+                .filter(|v| validators.contains(v))
+                .count();
+            for v in org.validators.iter() {
+                if count + 1 >= org.threshold {
+                    break;
+                }
+                if validators.insert(*v) {
+                    count += 1;
+                }
+            }
+        }
+        validators
+    }
+
+    // Groups the validators that are interchangeable: swapping two of them
+    // maps quorums to quorums. Returns, for each validator, its class, and for
+    // each class, the outputs of a counter of its correct validators.
+    fn interchangeable_classes(&mut self) -> Result<Classes, FbasError> {
+        let mut classes: BTreeMap<_, Vec<NodeIndex>> = BTreeMap::new();
+        for ni in self.fbas.validators.iter() {
+            let mut qsets = self.fbas.graph.neighbors(*ni).collect::<Vec<_>>();
+            qsets.sort();
+            let mut parents = self
+                .fbas
+                .graph
+                .neighbors_directed(*ni, Direction::Incoming)
+                .collect::<Vec<_>>();
+            parents.sort();
+            let assumed = self.fbas.assumed.contains_key(ni);
+            classes
+                .entry((qsets, parents, assumed))
+                .or_default()
+                .push(*ni);
+        }
+        let mut class_of = BTreeMap::new();
+        let mut counters = vec![];
+        let mut sink = LimitedSolver(&mut self.solver);
+        for (i, members) in classes.into_values().enumerate() {
+            let lits = members
+                .iter()
+                .map(|ni| !self.faulty[ni])
+                .collect::<Vec<_>>();
+            counters.push(at_least(&mut sink, &lits, lits.len())?);
+            class_of.extend(members.into_iter().map(|ni| (ni, i)));
+        }
+        Ok((class_of, counters))
+    }
+
+    fn minimal_blocking_set(mut self) -> Result<OrganizationFaultSet, FbasError> {
+        let all = self
+            .fbas
+            .validators
+            .iter()
+            .copied()
+            .collect::<BTreeSet<_>>();
+        let (class_of, counters) = self.interchangeable_classes()?;
+        let mut k = 0;
+        while k <= self.organizations.len() {
+            let Some((organizations, validators)) = self.solve_with_at_most(k)? else {
+                trace!(target: "SCP", "no blocking set of {} organizations", k);
+                k += 1;
+                continue;
+            };
+            let validators = self.saturate(&organizations, validators);
+            let available = all.difference(&validators).copied().collect();
+            let quorum = self.fbas.greatest_quorum_within(&available);
+            if quorum.is_empty() {
+                return self.fault_set(organizations, &validators);
+            }
+            // any blocking set leaves too few correct validators in some class
+            // to host this quorum (or a permutation of it)
+            let mut hosted: BTreeMap<usize, usize> = BTreeMap::new();
+            let limiter = &self.solver.cb().limiter;
+            for ni in self.fbas.minimal_quorum_within(&quorum, limiter)?.iter() {
+                *hosted.entry(class_of[ni]).or_default() += 1;
+            }
+            let mut clause = hosted
+                .into_iter()
+                .map(|(class, count)| !counters[class][count - 1])
+                .collect::<Vec<_>>();
+            LimitedSolver(&mut self.solver).add_clause(&mut clause)?;
+        }
+        Err(FbasError::InternalError(
+            "no blocking set even with all organizations faulty",
+        ))
+    }
+}
+
+impl FbasAnalyzer {
+    /// Finds a smallest set of organizations whose validators, by crashing,
+    /// leave no quorum among the remaining validators, i.e. halt the network.
+    /// The search shares the analyzer's `ResourceLimiter`.
+    pub fn minimal_organization_blocking_set(
+        &self,
+        organizations: &Organizations,
+        faults: OrganizationFaults,
+    ) -> Result<OrganizationFaultSet, FbasError> {
+        FaultSearch::new(self.fbas(), organizations, faults, self.resource_limiter())?
+            .minimal_blocking_set()
+    }
+
+    /// Finds a smallest set of organizations whose validators, by turning
+    /// Byzantine, allow two quorums that only intersect in faulty validators.
+    /// Returns `None` if no such set exists. The search shares the analyzer's
+    /// `ResourceLimiter`.
+    pub fn minimal_organization_splitting_set(
+        &self,
+        organizations: &Organizations,
+        faults: OrganizationFaults,
+    ) -> Result<Option<OrganizationFaultSet>, FbasError> {
+        FaultSearch::new(self.fbas(), organizations, faults, self.resource_limiter())?
+            .minimal_splitting_set()
+    }
+}
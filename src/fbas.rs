@@ -1,3 +1,4 @@
+use itertools::Itertools;
 use log::{trace, warn};
 use petgraph::graph::{DiGraph, NodeIndex};
 use std::{
@@ -5,14 +6,146 @@ use std::{
     fmt::Debug,
     rc::Rc,
 };
-use stellar_xdr::curr::{Limits, NodeId, PublicKey, ReadXdr, ScpQuorumSet};
+use stellar_xdr::curr::{Limits, NodeId, PublicKey, ReadXdr, ScpQuorumSet, Uint256, WriteXdr};
 
-use crate::{resource_limiter::ResourceLimiter, ResourceQuantity};
+use crate::{
+    resource_limiter::ResourceLimiter,
+    validation::{validate_quorum_set_map, QuorumSetViolation},
+    Phase, ResourceKind, ResourceQuantity,
+};
 
 const QUORUM_SET_MAX_DEPTH: u32 = 4;
+// stellar-core's `MAXIMUM_QUORUM_NODES`
+const QUORUM_SET_MAX_NODES: usize = 1000;
+
+/// Options controlling how quorum sets are parsed and validated.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseOptions {
+    /// Maximum number of nesting levels of a quorum set, counting the
+    /// top-level set. Deeper quorum sets fail with `MaxDepthExceeded`.
+    pub max_depth: u32,
+    /// Maximum total number of validators in a quorum set.
+    pub max_nodes: usize,
+    /// Also require every threshold to be a strict majority of its entries,
+    /// like stellar-core's `extraChecks`.
+    pub extra_checks: bool,
+    /// Reject quorum sets that fail validation with
+    /// `FbasError::InvalidQuorumSet`. Otherwise the violations are only
+    /// reported by `FbasAnalyzer::get_quorum_set_violations`.
+    pub strict: bool,
+    /// How to treat validators that appear in quorum sets but are not part of
+    /// the quorum set map.
+    pub unknown_validators: MissingNodePolicy,
+    /// How to treat nodes whose quorum set is missing (an empty buffer in
+    /// `from_quorum_set_map_buf`, or no `qset` in stellar-core's quorum
+    /// endpoint JSON).
+    pub missing_quorum_sets: MissingNodePolicy,
+    /// Drop nodes whose metadata marks them as inactive. They are then
+    /// treated according to `unknown_validators`.
+    pub only_active: bool,
+    /// Drop nodes whose metadata marks them as not validating. They are then
+    /// treated according to `unknown_validators`.
+    pub only_validating: bool,
+}
+
+/// How to treat a validator whose quorum set is not known.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum MissingNodePolicy {
+    /// The validator never takes part in any quorum, i.e. it cannot help
+    /// satisfy the quorum sets referencing it.
+    #[default]
+    Crashed,
+    /// The validator joins any quorum that needs it, but being honest, a
+    /// quorum split cannot include it on both sides.
+    Cooperating,
+    /// The validator may join any number of quorums, and quorums that only
+    /// intersect in Byzantine validators count as split.
+    Byzantine,
+    /// Fail with `FbasError::UnresolvedValidators`.
+    Fail,
+}
+
+/// Why a validator's quorum set is not known.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MissingReason {
+    UnknownValidator,
+    MissingQuorumSet,
+    /// Dropped by `ParseOptions::only_active` or
+    /// `ParseOptions::only_validating`.
+    Filtered,
+}
+
+/// A validator that a `MissingNodePolicy` was applied to.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MissingNode {
+    pub validator: String,
+    pub reason: MissingReason,
+    pub policy: MissingNodePolicy,
+}
+
+impl Default for ParseOptions {
+    fn default() -> Self {
+        Self {
+            max_depth: QUORUM_SET_MAX_DEPTH,
+            max_nodes: QUORUM_SET_MAX_NODES,
+            extra_checks: false,
+            strict: false,
+            unknown_validators: MissingNodePolicy::default(),
+            missing_quorum_sets: MissingNodePolicy::default(),
+            only_active: false,
+            only_validating: false,
+        }
+    }
+}
 
 pub(crate) type QuorumSetMap = BTreeMap<String, Rc<InternalScpQuorumSet>>;
 
+/// Descriptive attributes of a node, as published by stellarbeat. Fields
+/// missing from the input are `None`.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct NodeMetadata {
+    pub name: Option<String>,
+    pub home_domain: Option<String>,
+    pub isp: Option<String>,
+    pub organization_id: Option<String>,
+    pub active: Option<bool>,
+    pub is_validating: Option<bool>,
+}
+
+impl std::fmt::Display for NodeMetadata {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name.as_deref().unwrap_or("unnamed node"))?;
+        if let Some(home_domain) = &self.home_domain {
+            write!(f, " of {home_domain}")?;
+        }
+        if let Some(isp) = &self.isp {
+            write!(f, " in {isp}")?;
+        }
+        Ok(())
+    }
+}
+
+pub(crate) type NodeMetadataMap = BTreeMap<String, NodeMetadata>;
+
+/// The local node's view of the last SCP slot, as reported in the `qset`
+/// object of stellar-core's `quorum` endpoint. Fields missing from the input
+/// are `None` or empty.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct StellarCoreQuorumInfo {
+    /// The local node.
+    pub node: String,
+    pub phase: Option<String>,
+    /// Validators of the local quorum set that agree with the local node.
+    pub agree: Option<u32>,
+    pub disagree: Option<u32>,
+    /// Validators of the local quorum set not heard from in the slot.
+    pub missing: Vec<String>,
+}
+
+/// The XDR-serialized node ids and their quorum sets, paired by index, as
+/// taken by `from_quorum_set_map_buf`.
+pub type QuorumSetMapBuf = (Vec<Vec<u8>>, Vec<Vec<u8>>);
+
 /// This is the internal representation of a quorum set. The Qset structure must
 /// be explicitly specified (by validator's declaration). You can't say my inner
 /// qset is "another validator's qset". Because of that, the `Qset` structure
@@ -58,13 +191,70 @@ impl Vertex {
     }
 }
 
+/// Where in the input a parse or decoding error occurred. Fields that do not
+/// apply to the input format are left empty.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ErrorLocation {
+    pub file: Option<String>,
+    /// JSON pointer (RFC 6901) to the offending value, also used for TOML
+    /// documents.
+    pub json_pointer: Option<String>,
+    /// The validator whose entry failed to parse.
+    pub node: Option<String>,
+    /// Indices of the inner sets leading from the validator's top-level
+    /// quorum set to the offending one.
+    pub qset_path: Vec<usize>,
+    /// Index of the offending buffer in `from_quorum_set_map_buf`.
+    pub buffer_index: Option<usize>,
+}
+
+impl ErrorLocation {
+    pub(crate) fn buffer(index: usize) -> Self {
+        Self {
+            buffer_index: Some(index),
+            ..Default::default()
+        }
+    }
+}
+
+impl std::fmt::Display for ErrorLocation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut parts = vec![];
+        if let Some(file) = &self.file {
+            parts.push(format!("file {file}"));
+        }
+        if let Some(pointer) = &self.json_pointer {
+            parts.push(format!("pointer {pointer:?}"));
+        }
+        if let Some(node) = &self.node {
+            parts.push(format!("node {node}"));
+        }
+        if !self.qset_path.is_empty() {
+            parts.push(format!("qset path {:?}", self.qset_path));
+        }
+        if let Some(index) = self.buffer_index {
+            parts.push(format!("buffer {index}"));
+        }
+        if parts.is_empty() {
+            write!(f, "unknown location")
+        } else {
+            write!(f, "{}", parts.join(", "))
+        }
+    }
+}
+
 #[derive(Debug)]
 pub enum FbasError {
-    ParseError(&'static str),
+    ParseError(&'static str, Box<ErrorLocation>),
     MaxDepthExceeded,
-    XdrDecodingError(&'static str),
+    XdrDecodingError(&'static str, Box<ErrorLocation>, stellar_xdr::curr::Error),
     InternalError(&'static str),
-    ResourcelimitExceeded(ResourceQuantity),
+    ResourcelimitExceeded(ResourceQuantity, ResourceKind, Phase),
+    ProofUnavailable(&'static str),
+    Interrupted,
+    InvalidQuorumSet(Vec<QuorumSetViolation>),
+    UnresolvedValidators(Vec<MissingNode>),
+    UnknownValidator(String),
 }
 
 impl std::error::Error for FbasError {}
@@ -72,33 +262,97 @@ impl std::error::Error for FbasError {}
 impl std::fmt::Display for FbasError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
-            FbasError::ParseError(msg) => write!(f, "Parse error: {msg}"),
+            FbasError::ParseError(msg, location) => write!(f, "Parse error: {msg} ({location})"),
             FbasError::MaxDepthExceeded => write!(f, "Maximum quorum set depth exceeded"),
-            FbasError::XdrDecodingError(msg) => write!(f, "XDR decoding error: {msg}"),
+            FbasError::XdrDecodingError(msg, location, err) => {
+                write!(f, "XDR decoding error: {msg} ({location}): {err}")
+            }
             FbasError::InternalError(msg) => write!(f, "Internal error (likely a bug): {msg}"),
-            FbasError::ResourcelimitExceeded(resource_quantity) => write!(
+            FbasError::ResourcelimitExceeded(resource_quantity, resource, phase) => write!(
                 f,
-                "Resource limits exceeded -- Time elapsed: {} ms, Memory usage: {} bytes",
+                "Resource limits exceeded ({}) during {} phase -- Time elapsed: {} ms, Memory usage: {} bytes, Conflicts: {}, Propagations: {}, Clauses: {}",
+                resource,
+                phase,
                 resource_quantity.time.as_millis(),
-                resource_quantity.mem_bytes
+                resource_quantity.mem_bytes,
+                resource_quantity.conflicts,
+                resource_quantity.propagations,
+                resource_quantity.clauses
+            ),
+            FbasError::ProofUnavailable(msg) => write!(f, "Proof unavailable: {msg}"),
+            FbasError::Interrupted => write!(f, "Analysis interrupted"),
+            FbasError::InvalidQuorumSet(violations) => {
+                write!(f, "Invalid quorum set: ")?;
+                match violations.as_slice() {
+                    [] => Ok(()),
+                    [violation] => write!(f, "{violation}"),
+                    [violation, rest @ ..] => {
+                        write!(f, "{violation} (and {} more)", rest.len())
+                    }
+                }
+            }
+            FbasError::UnresolvedValidators(nodes) => write!(
+                f,
+                "Validators without a known quorum set: {}",
+                nodes.iter().map(|n| n.validator.as_str()).join(", ")
             ),
+            FbasError::UnknownValidator(validator) => {
+                write!(f, "Validator {validator} is not in the network")
+            }
+        }
+    }
+}
+
+pub(crate) fn node_id_to_string(node_id: &NodeId) -> String {
+    match &node_id.0 {
+        PublicKey::PublicKeyTypeEd25519(key) => {
+            stellar_strkey::ed25519::PublicKey(key.0).to_string()
         }
     }
 }
 
+fn node_id_from_string(node: &str) -> Result<NodeId, FbasError> {
+    let key = stellar_strkey::ed25519::PublicKey::from_string(node).map_err(|_| {
+        FbasError::ParseError(
+            "validator is not a valid public key strkey",
+            Box::new(ErrorLocation {
+                node: Some(node.to_string()),
+                ..Default::default()
+            }),
+        )
+    })?;
+    Ok(NodeId(PublicKey::PublicKeyTypeEd25519(Uint256(key.0))))
+}
+
+impl InternalScpQuorumSet {
+    fn to_scp_quorum_set(&self) -> Result<ScpQuorumSet, FbasError> {
+        let validators = self
+            .validators
+            .iter()
+            .map(|v| node_id_from_string(v))
+            .collect::<Result<Vec<_>, _>>()?;
+        let inner_sets = self
+            .inner_sets
+            .iter()
+            .map(|qs| qs.to_scp_quorum_set())
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(ScpQuorumSet {
+            threshold: self.threshold,
+            validators: validators
+                .try_into()
+                .map_err(|_| FbasError::InternalError("too many validators in quorum set"))?,
+            inner_sets: inner_sets
+                .try_into()
+                .map_err(|_| FbasError::InternalError("too many inner quorum sets"))?,
+        })
+    }
+}
+
 impl From<ScpQuorumSet> for InternalScpQuorumSet {
     fn from(qset: ScpQuorumSet) -> Self {
         InternalScpQuorumSet {
             threshold: qset.threshold,
-            validators: qset
-                .validators
-                .iter()
-                .map(|node_id| match &node_id.0 {
-                    PublicKey::PublicKeyTypeEd25519(key) => {
-                        stellar_strkey::ed25519::PublicKey(key.0).to_string()
-                    }
-                })
-                .collect(),
+            validators: qset.validators.iter().map(node_id_to_string).collect(),
             inner_sets: qset
                 .inner_sets
                 .iter()
@@ -110,8 +364,22 @@ impl From<ScpQuorumSet> for InternalScpQuorumSet {
 
 #[derive(Default, Debug)]
 pub(crate) struct Fbas {
+    // the quorum sets as parsed, before applying any `MissingNodePolicy`
+    pub quorum_set_map: QuorumSetMap,
+    pub metadata: NodeMetadataMap,
     pub graph: DiGraph<Vertex, ()>,
     pub validators: Vec<NodeIndex>,
+    // validators whose quorum set was assumed by a `MissingNodePolicy`
+    pub assumed: BTreeMap<NodeIndex, MissingNodePolicy>,
+    pub missing: Vec<MissingNode>,
+    // validators outside the transitive closure this FBAS was restricted to
+    pub outside: Vec<String>,
+    pub quorum_info: Option<StellarCoreQuorumInfo>,
+    // validation failures tolerated since `ParseOptions::strict` is off
+    pub violations: Vec<QuorumSetViolation>,
+    // organizations whose collapsed quorum set is stricter than their validators'
+    pub approximate: Vec<String>,
+    pub options: ParseOptions,
 }
 
 impl Fbas {
@@ -128,11 +396,116 @@ impl Fbas {
         }
     }
 
-    fn from_quorum_set_map(
-        qsm: QuorumSetMap,
+    // Applies the `MissingNodePolicy` to every validator without a quorum set.
+    // Cooperating and Byzantine validators are given a vacuous quorum set,
+    // which is satisfied by any quorum.
+    fn resolve_missing_nodes(
+        qsm: &mut QuorumSetMap,
+        missing_qsets: &BTreeSet<String>,
+        filtered: &BTreeSet<String>,
+        options: &ParseOptions,
+    ) -> Result<Vec<MissingNode>, FbasError> {
+        fn collect<'a>(qset: &'a InternalScpQuorumSet, out: &mut BTreeSet<&'a String>) {
+            out.extend(qset.validators.iter());
+            qset.inner_sets.iter().for_each(|inner| collect(inner, out));
+        }
+        let mut referenced = BTreeSet::new();
+        qsm.values().for_each(|qset| collect(qset, &mut referenced));
+
+        let mut missing = missing_qsets
+            .iter()
+            .filter(|v| !qsm.contains_key(*v))
+            .map(|v| MissingNode {
+                validator: v.clone(),
+                reason: MissingReason::MissingQuorumSet,
+                policy: options.missing_quorum_sets,
+            })
+            .collect::<Vec<_>>();
+        missing.extend(
+            referenced
+                .into_iter()
+                .filter(|v| {
+                    !qsm.contains_key(*v) && !missing_qsets.contains(*v) && !filtered.contains(*v)
+                })
+                .map(|v| MissingNode {
+                    validator: v.clone(),
+                    reason: MissingReason::UnknownValidator,
+                    policy: options.unknown_validators,
+                }),
+        );
+        missing.extend(filtered.iter().map(|v| MissingNode {
+            validator: v.clone(),
+            reason: MissingReason::Filtered,
+            policy: options.unknown_validators,
+        }));
+
+        let failed = missing
+            .iter()
+            .filter(|n| n.policy == MissingNodePolicy::Fail)
+            .cloned()
+            .collect::<Vec<_>>();
+        if !failed.is_empty() {
+            return Err(FbasError::UnresolvedValidators(failed));
+        }
+
+        for node in missing.iter() {
+            warn!(target: "SCP",
+                "validator {} has no known quorum set ({:?}), treating it as {:?}",
+                node.validator, node.reason, node.policy
+            );
+            if node.policy != MissingNodePolicy::Crashed {
+                let vacuous = InternalScpQuorumSet {
+                    threshold: 0,
+                    validators: vec![],
+                    inner_sets: vec![],
+                };
+                qsm.insert(node.validator.clone(), Rc::new(vacuous));
+            }
+        }
+        Ok(missing)
+    }
+
+    // Removes the nodes excluded by `ParseOptions::only_active` and
+    // `ParseOptions::only_validating`. Nodes without metadata are kept.
+    pub(crate) fn filter_nodes(
+        qsm: &mut QuorumSetMap,
+        metadata: &NodeMetadataMap,
+        options: &ParseOptions,
+    ) -> BTreeSet<String> {
+        let filtered = metadata
+            .iter()
+            .filter(|(_, meta)| {
+                (options.only_active && meta.active == Some(false))
+                    || (options.only_validating && meta.is_validating == Some(false))
+            })
+            .map(|(node, _)| node.clone())
+            .collect::<BTreeSet<_>>();
+        qsm.retain(|node, _| !filtered.contains(node));
+        filtered
+    }
+
+    pub(crate) fn from_quorum_set_map(
+        mut qsm: QuorumSetMap,
+        missing_qsets: &BTreeSet<String>,
+        metadata: NodeMetadataMap,
+        options: &ParseOptions,
         resource_limiter: &ResourceLimiter,
     ) -> Result<Self, FbasError> {
-        let mut fbas = Fbas::default();
+        let quorum_set_map = qsm.clone();
+        let filtered = Self::filter_nodes(&mut qsm, &metadata, options);
+        let violations = validate_quorum_set_map(&qsm, options);
+        if options.strict && !violations.is_empty() {
+            return Err(FbasError::InvalidQuorumSet(violations));
+        }
+
+        let missing = Self::resolve_missing_nodes(&mut qsm, missing_qsets, &filtered, options)?;
+        let mut fbas = Fbas {
+            quorum_set_map,
+            metadata,
+            violations,
+            options: options.clone(),
+            ..Default::default()
+        };
         let mut known_validators = BTreeMap::new();
         let mut known_qsets = BTreeMap::new();
 
@@ -141,6 +514,12 @@ impl Fbas {
             let idx = fbas.add_validator(node_str.clone());
             known_validators.insert(node_str, idx);
         }
+        for node in missing.iter() {
+            if let Some(&idx) = known_validators.get(&node.validator) {
+                fbas.assumed.insert(idx, node.policy);
+            }
+        }
+        fbas.missing = missing;
 
         // Second pass: process quorum sets and create connections
         for (node_str, qset) in qsm.iter() {
@@ -150,6 +529,7 @@ impl Fbas {
             let q_idx = fbas.process_scp_quorum_set(
                 qset,
                 0,
+                options.max_depth,
                 &known_validators,
                 &mut known_qsets,
                 resource_limiter,
@@ -171,13 +551,14 @@ impl Fbas {
         &mut self,
         qset: &InternalScpQuorumSet,
         curr_depth: u32,
+        max_depth: u32,
         known_validators: &BTreeMap<&String, NodeIndex>,
         known_qsets: &mut BTreeMap<Qset, NodeIndex>,
         resource_limiter: &ResourceLimiter,
     ) -> Result<NodeIndex, FbasError> {
         resource_limiter.measure_and_enforce_limits()?;
 
-        if curr_depth == QUORUM_SET_MAX_DEPTH {
+        if curr_depth >= max_depth {
             return Err(FbasError::MaxDepthExceeded);
         }
 
@@ -191,7 +572,8 @@ impl Fbas {
             if let Some(&idx) = known_validators.get(validator) {
                 new_qset.validators.insert(idx);
             } else {
-                warn!(target: "SCP", "validator {} is unknown", validator);
+                // crashed, see `resolve_missing_nodes`
+                trace!(target: "SCP", "validator {} is unknown", validator);
             }
         }
 
@@ -200,6 +582,7 @@ impl Fbas {
             let qidx = self.process_scp_quorum_set(
                 inner_qset,
                 curr_depth + 1,
+                max_depth,
                 known_validators,
                 known_qsets,
                 resource_limiter,
@@ -230,43 +613,137 @@ impl Fbas {
     pub fn from_quorum_set_map_buf<T: AsRef<[u8]>, I: ExactSizeIterator<Item = T>>(
         nodes: I,
         quorum_sets: I,
+        options: &ParseOptions,
         resource_limiter: &ResourceLimiter,
     ) -> Result<Self, FbasError> {
         if nodes.len() != quorum_sets.len() {
             return Err(FbasError::ParseError(
                 "length in nodes and quorum_sets do not match",
+                Box::default(),
             ));
         }
 
         let mut quorum_set_map = QuorumSetMap::new();
+        let mut missing_qsets = BTreeSet::new();
 
-        for (node_buf, qset_buf) in nodes.zip(quorum_sets) {
-            let node = NodeId::from_xdr(node_buf, Limits::none())
-                .map_err(|_| FbasError::XdrDecodingError("NodeId cannot be decoded from xdr"))?;
-            let node_str = match &node.0 {
-                PublicKey::PublicKeyTypeEd25519(key) => {
-                    stellar_strkey::ed25519::PublicKey(key.0).to_string()
-                }
-            };
+        for (i, (node_buf, qset_buf)) in nodes.zip(quorum_sets).enumerate() {
+            let node = NodeId::from_xdr(node_buf, Limits::none()).map_err(|err| {
+                FbasError::XdrDecodingError(
+                    "NodeId cannot be decoded from xdr",
+                    Box::new(ErrorLocation::buffer(i)),
+                    err,
+                )
+            })?;
+            let node_str = node_id_to_string(&node);
             if !qset_buf.as_ref().is_empty() {
-                let qset = ScpQuorumSet::from_xdr(qset_buf, Limits::none()).map_err(|_| {
-                    FbasError::XdrDecodingError("ScpQuorumSet cannot be decoded from xdr")
+                let qset = ScpQuorumSet::from_xdr(qset_buf, Limits::none()).map_err(|err| {
+                    FbasError::XdrDecodingError(
+                        "ScpQuorumSet cannot be decoded from xdr",
+                        Box::new(ErrorLocation {
+                            node: Some(node_str.clone()),
+                            ..ErrorLocation::buffer(i)
+                        }),
+                        err,
+                    )
                 })?;
                 quorum_set_map.insert(node_str, Rc::new(qset.into()));
             } else {
                 warn!(target: "SCP", "Validator {}'s quorum set is empty", node_str);
+                missing_qsets.insert(node_str);
             }
         }
 
-        Self::from_quorum_set_map(quorum_set_map, resource_limiter)
+        Self::from_quorum_set_map(
+            quorum_set_map,
+            &missing_qsets,
+            NodeMetadataMap::new(),
+            options,
+            resource_limiter,
+        )
+    }
+
+    /// Serializes the quorum set map into the node and quorum set buffers
+    /// taken by `from_quorum_set_map_buf`. Nodes whose quorum set is missing
+    /// get an empty quorum set buffer.
+    pub fn to_quorum_set_map_buf(&self) -> Result<QuorumSetMapBuf, FbasError> {
+        let encode_error = |_| FbasError::InternalError("value cannot be encoded to xdr");
+        let mut nodes = vec![];
+        let mut quorum_sets = vec![];
+        for (node, qset) in self.quorum_set_map.iter() {
+            nodes.push(
+                node_id_from_string(node)?
+                    .to_xdr(Limits::none())
+                    .map_err(encode_error)?,
+            );
+            quorum_sets.push(
+                qset.to_scp_quorum_set()?
+                    .to_xdr(Limits::none())
+                    .map_err(encode_error)?,
+            );
+        }
+        for node in self
+            .missing
+            .iter()
+            .filter(|n| n.reason == MissingReason::MissingQuorumSet)
+        {
+            nodes.push(
+                node_id_from_string(&node.validator)?
+                    .to_xdr(Limits::none())
+                    .map_err(encode_error)?,
+            );
+            quorum_sets.push(vec![]);
+        }
+        Ok((nodes, quorum_sets))
+    }
+
+    pub fn from_scp_history_paths<P: AsRef<str>>(
+        paths: &[P],
+        max_ledger: Option<u32>,
+        options: &ParseOptions,
+        resource_limiter: &ResourceLimiter,
+    ) -> Result<Self, FbasError> {
+        let (quorum_set_map, unresolved) =
+            crate::history::quorum_set_map_from_scp_history(paths, max_ledger, resource_limiter)?;
+        Self::from_quorum_set_map(
+            quorum_set_map,
+            &unresolved,
+            NodeMetadataMap::new(),
+            options,
+            resource_limiter,
+        )
     }
 
     #[cfg(any(feature = "json", test))]
     pub fn from_json_path(
         path: &str,
+        options: &ParseOptions,
+        resource_limiter: &ResourceLimiter,
+    ) -> Result<Self, FbasError> {
+        let input = crate::json_parser::parse_json(path)?;
+        let mut fbas = Self::from_quorum_set_map(
+            input.quorum_set_map,
+            &input.missing_qsets,
+            input.metadata,
+            options,
+            resource_limiter,
+        )?;
+        fbas.quorum_info = input.quorum_info;
+        Ok(fbas)
+    }
+
+    #[cfg(any(feature = "toml", test))]
+    pub fn from_toml_paths<S: AsRef<str>, P: AsRef<str>>(
+        configs: &[(S, P)],
+        options: &ParseOptions,
         resource_limiter: &ResourceLimiter,
     ) -> Result<Self, FbasError> {
-        let quorum_set_map = crate::json_parser::quorum_set_map_from_json(path)?;
-        Self::from_quorum_set_map(quorum_set_map, resource_limiter)
+        let quorum_set_map = crate::toml_parser::quorum_set_map_from_toml(configs)?;
+        Self::from_quorum_set_map(
+            quorum_set_map,
+            &BTreeSet::new(),
+            NodeMetadataMap::new(),
+            options,
+            resource_limiter,
+        )
     }
 }
@@ -1,12 +1,18 @@
 use crate::{
-    fbas::{Fbas, FbasError},
-    resource_limiter::ResourceLimiter,
+    fbas::{
+        Fbas, FbasError, MissingNode, MissingNodePolicy, NodeMetadata, ParseOptions,
+        QuorumSetMapBuf, StellarCoreQuorumInfo,
+    },
+    organizations::{OrganizationGrouping, Organizations},
+    proof::{AnalyzerCallbacks, ClauseSink, DimacsFormula},
+    resource_limiter::{Phase, ResourceLimiter},
+    validation::QuorumSetViolation,
 };
 use batsat::{interface::SolveResult, lbool, theory, Lit, Solver, SolverInterface, Var};
 use itertools::Itertools;
 use log::{trace, warn};
 use petgraph::graph::NodeIndex;
-use std::collections::BTreeMap;
+use std::{collections::BTreeMap, time::Duration};
 
 // Two imaginary quorums A and B, and we have FBAS system with V vertices. Note
 // that a vertex can be either a validator or a qset. The relation of each
@@ -41,12 +47,30 @@ use std::collections::BTreeMap;
 // proven there are no disjoint quorums.
 
 #[derive(Default)]
-struct VarManager {
+pub(crate) struct VarManager {
     // stores variables representing nodes in quorums A and B
     node_quorum_membership: BTreeMap<NodeIndex, (Var, Var)>,
 }
 
 impl VarManager {
+    // Allocates the membership variables the same way `construct_vars` does,
+    // for solvers other than the analyzer's own.
+    pub(crate) fn new<S: ClauseSink>(fbas: &Fbas, sink: &mut S) -> Self {
+        let node_count = fbas.graph.node_count();
+        let vars = (0..2 * node_count)
+            .map(|_| sink.new_var())
+            .collect::<Vec<_>>();
+        let node_quorum_membership = fbas
+            .graph
+            .node_indices()
+            .enumerate()
+            .map(|(i, ni)| (ni, (vars[i], vars[i + node_count])))
+            .collect();
+        Self {
+            node_quorum_membership,
+        }
+    }
+
     fn quorum_a(&self, ni: &NodeIndex) -> Result<Var, FbasError> {
         Ok(self
             .node_quorum_membership
@@ -71,11 +95,83 @@ impl VarManager {
     }
 }
 
+// Feeds clauses into the solver, enforcing the resource limits on every clause.
+pub(crate) struct LimitedSolver<'a>(pub &'a mut Solver<AnalyzerCallbacks>);
+
+impl ClauseSink for LimitedSolver<'_> {
+    fn new_var(&mut self) -> Var {
+        self.0.new_var_default()
+    }
+
+    fn add_clause(&mut self, clause: &mut Vec<Lit>) -> Result<(), FbasError> {
+        self.0.cb().limiter.add_clause();
+        self.0.cb().limiter.measure_and_enforce_limits()?;
+        self.0.add_clause_reuse(clause);
+        Ok(())
+    }
+}
+
+// Solves under `assumptions`, resuming when paused at a propagation checkpoint,
+// and reads a satisfying assignment with `on_sat` (which is given the value of
+// each literal). Returns `None` if unsatisfiable. The work is added to the
+// current phase, which the caller enters once for all runs of a query.
+pub(crate) fn solve_resumable<T>(
+    solver: &mut Solver<AnalyzerCallbacks>,
+    assumptions: &[Lit],
+    mut on_sat: impl FnMut(&dyn Fn(Lit) -> bool) -> T,
+) -> Result<Option<T>, FbasError> {
+    let limiter = solver.cb().limiter.clone();
+    let mut propagations = solver.num_propagations();
+    let mut th = theory::EmptyTheory::new();
+    loop {
+        let result = match solver.solve_limited_th_full(&mut th, assumptions) {
+            SolveResult::Sat(model) => {
+                Some(Some(on_sat(&|lit| model.value_lit(lit) == lbool::TRUE)))
+            }
+            SolveResult::Unsat(_) => Some(None),
+            SolveResult::Unknown(_) => None,
+        };
+        limiter.add_propagations(solver.num_propagations() - propagations);
+        propagations = solver.num_propagations();
+        match result {
+            Some(result) => return Ok(result),
+            None if limiter.resume_at_checkpoint() => continue,
+            None => {
+                limiter.measure_and_enforce_limits()?;
+                return Err(FbasError::InternalError(
+                    "solver stopped without exceeding the resource limits",
+                ));
+            }
+        }
+    }
+}
+
 pub struct FbasAnalyzer {
     fbas: Fbas,
-    solver: Solver<ResourceLimiter>,
+    solver: Solver<AnalyzerCallbacks>,
     status: SolveStatus,
     vars: VarManager,
+    statistics: SolveStatistics,
+}
+
+/// Statistics about the size of the problem and the work done to solve it.
+/// Times are measured by the `ResourceLimiter`'s clock.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SolveStatistics {
+    pub graph_vertices: usize,
+    pub graph_edges: usize,
+    pub validators: usize,
+    pub cnf_vars: u64,
+    pub cnf_clauses: u64,
+    /// Auxiliary variables introduced by the Tseitin transformation.
+    pub tseitin_vars: u64,
+    pub conflicts: u64,
+    pub decisions: u64,
+    pub propagations: u64,
+    /// Time spent parsing the input and constructing the formula.
+    pub build_time: Duration,
+    pub solve_time: Duration,
+    pub peak_mem_bytes: usize,
 }
 
 #[derive(Clone, Default, PartialEq)]
@@ -110,7 +206,49 @@ impl FbasAnalyzer {
         quorum_set: I,
         resource_limiter: ResourceLimiter,
     ) -> Result<Self, FbasError> {
-        let fbas = Fbas::from_quorum_set_map_buf(nodes, quorum_set, &resource_limiter)?;
+        Self::from_quorum_set_map_buf_with_options(
+            nodes,
+            quorum_set,
+            &ParseOptions::default(),
+            resource_limiter,
+        )
+    }
+
+    pub fn from_quorum_set_map_buf_with_options<T: AsRef<[u8]>, I: ExactSizeIterator<Item = T>>(
+        nodes: I,
+        quorum_set: I,
+        options: &ParseOptions,
+        resource_limiter: ResourceLimiter,
+    ) -> Result<Self, FbasError> {
+        let fbas = Fbas::from_quorum_set_map_buf(nodes, quorum_set, options, &resource_limiter)?;
+        Self::from_fbas(fbas, resource_limiter)
+    }
+
+    /// Builds the analyzer from the quorum sets used in SCP history archive
+    /// files (uncompressed `scp-*.xdr` checkpoint files), taking each node's
+    /// latest quorum set up to ledger `max_ledger` (or the last ledger in the
+    /// files). Nodes whose quorum set is not in the files are treated
+    /// according to `ParseOptions::missing_quorum_sets`.
+    pub fn from_scp_history_paths<P: AsRef<str>>(
+        paths: &[P],
+        max_ledger: Option<u32>,
+        resource_limiter: ResourceLimiter,
+    ) -> Result<Self, FbasError> {
+        Self::from_scp_history_paths_with_options(
+            paths,
+            max_ledger,
+            &ParseOptions::default(),
+            resource_limiter,
+        )
+    }
+
+    pub fn from_scp_history_paths_with_options<P: AsRef<str>>(
+        paths: &[P],
+        max_ledger: Option<u32>,
+        options: &ParseOptions,
+        resource_limiter: ResourceLimiter,
+    ) -> Result<Self, FbasError> {
+        let fbas = Fbas::from_scp_history_paths(paths, max_ledger, options, &resource_limiter)?;
         Self::from_fbas(fbas, resource_limiter)
     }
 
@@ -119,7 +257,37 @@ impl FbasAnalyzer {
         path: &str,
         resource_limiter: ResourceLimiter,
     ) -> Result<Self, FbasError> {
-        let fbas = Fbas::from_json_path(path, &resource_limiter)?;
+        Self::from_json_path_with_options(path, &ParseOptions::default(), resource_limiter)
+    }
+
+    #[cfg(any(feature = "json", test))]
+    pub fn from_json_path_with_options(
+        path: &str,
+        options: &ParseOptions,
+        resource_limiter: ResourceLimiter,
+    ) -> Result<Self, FbasError> {
+        let fbas = Fbas::from_json_path(path, options, &resource_limiter)?;
+        Self::from_fbas(fbas, resource_limiter)
+    }
+
+    /// Builds the analyzer from stellar-core configurations, synthesizing each
+    /// node's quorum set from its `HOME_DOMAINS` and `VALIDATORS`. `configs`
+    /// pairs each node's public key with the path of its configuration file.
+    #[cfg(any(feature = "toml", test))]
+    pub fn from_toml_paths<S: AsRef<str>, P: AsRef<str>>(
+        configs: &[(S, P)],
+        resource_limiter: ResourceLimiter,
+    ) -> Result<Self, FbasError> {
+        Self::from_toml_paths_with_options(configs, &ParseOptions::default(), resource_limiter)
+    }
+
+    #[cfg(any(feature = "toml", test))]
+    pub fn from_toml_paths_with_options<S: AsRef<str>, P: AsRef<str>>(
+        configs: &[(S, P)],
+        options: &ParseOptions,
+        resource_limiter: ResourceLimiter,
+    ) -> Result<Self, FbasError> {
+        let fbas = Fbas::from_toml_paths(configs, options, &resource_limiter)?;
         Self::from_fbas(fbas, resource_limiter)
     }
 
@@ -127,14 +295,32 @@ impl FbasAnalyzer {
         fbas: Fbas,
         resource_limiter: ResourceLimiter,
     ) -> Result<Self, FbasError> {
+        resource_limiter.enter_phase(Phase::Formula);
+        let clauses_before = resource_limiter.get_usage().clauses;
+        let statistics = SolveStatistics {
+            graph_vertices: fbas.graph.node_count(),
+            graph_edges: fbas.graph.edge_count(),
+            validators: fbas.validators.len(),
+            ..Default::default()
+        };
         let mut analyzer = Self {
             fbas,
-            solver: Solver::new(Default::default(), resource_limiter),
+            solver: Solver::new(Default::default(), AnalyzerCallbacks::new(resource_limiter)),
             status: SolveStatus::UNKNOWN,
             vars: VarManager::default(),
+            statistics,
         };
         analyzer.construct_vars()?;
         analyzer.construct_formula()?;
+
+        let limiter = &analyzer.solver.cb().limiter;
+        limiter.measure(false);
+        let cnf_vars = analyzer.solver.num_vars() as u64;
+        analyzer.statistics.cnf_vars = cnf_vars;
+        analyzer.statistics.cnf_clauses = limiter.get_usage().clauses - clauses_before;
+        analyzer.statistics.tseitin_vars = cnf_vars - 2 * analyzer.fbas.graph.node_count() as u64;
+        analyzer.statistics.build_time = limiter.get_usage().time;
+        analyzer.statistics.peak_mem_bytes = limiter.get_peak_mem_bytes();
         Ok(analyzer)
     }
 
@@ -152,7 +338,7 @@ impl FbasAnalyzer {
             .map(|_| self.solver.new_var_default())
             .collect::<Vec<_>>();
         for (i, ni) in self.fbas.graph.node_indices().enumerate() {
-            self.solver.cb().measure_and_enforce_limits()?;
+            self.solver.cb().limiter.measure_and_enforce_limits()?;
             self.vars
                 .node_quorum_membership
                 .insert(ni, (vars[i], vars[i + node_count]));
@@ -160,112 +346,153 @@ impl FbasAnalyzer {
         Ok(())
     }
 
-    fn add_clause_limited(
-        solver: &mut Solver<ResourceLimiter>,
-        clause: &mut Vec<Lit>,
-    ) -> Result<bool, FbasError> {
-        solver.cb().measure_and_enforce_limits()?;
-        Ok(solver.add_clause_reuse(clause))
-    }
-
     fn construct_formula(&mut self) -> Result<(), FbasError> {
-        let fbas = &self.fbas;
         // vars representing quorum membership must be pre-constructed
-        if self.solver.num_vars() as usize != fbas.graph.node_count() * 2 {
+        if self.solver.num_vars() as usize != self.fbas.graph.node_count() * 2 {
             return Err(FbasError::InternalError(
                 "solver internal variables count does not match node count",
             ));
         }
+        Self::add_formula_clauses(&self.fbas, &self.vars, &mut LimitedSolver(&mut self.solver))?;
+        trace!(
+            target: "SCP",
+            "FbasAnalyzer num_vars = {}, num_clauses = {}",
+            self.solver.num_vars(),
+            self.solver.num_clauses()
+        );
+        Ok(())
+    }
 
-        // formula 1: both quorums are non-empty -- at least one *validator* must
-        // exist in each quorum
-        let mut quorum_a_non_empty = fbas
-            .validators
-            .iter()
-            .map(|ni| self.vars.lit_in_quorum_a(ni, true))
-            .collect::<Result<Vec<Lit>, FbasError>>()?;
-        Self::add_clause_limited(&mut self.solver, &mut quorum_a_non_empty)?;
+    pub(crate) fn add_formula_clauses<S: ClauseSink>(
+        fbas: &Fbas,
+        vars: &VarManager,
+        sink: &mut S,
+    ) -> Result<(), FbasError> {
+        Self::add_formula_clauses_with_faults(fbas, vars, &BTreeMap::new(), &BTreeMap::new(), sink)
+    }
 
-        let mut quorum_b_non_empty = fbas
-            .validators
-            .iter()
-            .map(|ni| self.vars.lit_in_quorum_b(ni, true))
-            .collect::<Result<Vec<Lit>, FbasError>>()?;
-        Self::add_clause_limited(&mut self.solver, &mut quorum_b_non_empty)?;
+    // Same as `add_formula_clauses`, where validators in `faulty` may turn
+    // Byzantine if their literal is true: such a validator can be in both
+    // quorums, its quorum set need not be satisfied, and it does not make a
+    // quorum non-empty. Validators in `shared` may only be in both quorums if
+    // their literal is true, but are otherwise correct. Without faulty or
+    // shared validators the clauses are identical.
+    pub(crate) fn add_formula_clauses_with_faults<S: ClauseSink>(
+        fbas: &Fbas,
+        vars: &VarManager,
+        faulty: &BTreeMap<NodeIndex, Lit>,
+        shared: &BTreeMap<NodeIndex, Lit>,
+        sink: &mut S,
+    ) -> Result<(), FbasError> {
+        // formula 1: both quorums are non-empty
+        Self::add_non_empty_clauses(fbas, &|ni| vars.lit_in_quorum_a(ni, true), faulty, sink)?;
+        Self::add_non_empty_clauses(fbas, &|ni| vars.lit_in_quorum_b(ni, true), faulty, sink)?;
 
         // formula 2: two quorums do not intersect -- no *validator* can appear in
-        // both quorums
+        // both quorums, except for Byzantine and shared ones
         for ni in fbas.validators.iter() {
-            Self::add_clause_limited(
-                &mut self.solver,
-                &mut vec![
-                    self.vars.lit_in_quorum_a(ni, false)?,
-                    self.vars.lit_in_quorum_b(ni, false)?,
-                ],
-            )?;
+            if fbas.assumed.get(ni) == Some(&MissingNodePolicy::Byzantine) {
+                continue;
+            }
+            let mut clause = vec![
+                vars.lit_in_quorum_a(ni, false)?,
+                vars.lit_in_quorum_b(ni, false)?,
+            ];
+            clause.extend(faulty.get(ni));
+            clause.extend(shared.get(ni));
+            sink.add_clause(&mut clause)?;
         }
 
-        // formula 3: qset relation for each vertex must be satisfied. Variable
-        // naming follows "Final formula encoding that A and B are quorums" in
-        // `method.md`, assuming quorum A.
-        let mut add_clauses_for_quorum_relations = |node_in_quorum: &dyn Fn(
-            &NodeIndex,
-            bool,
-        ) -> Result<
-            Lit,
-            FbasError,
-        >|
-         -> Result<(), FbasError> {
-            fbas.graph.node_indices().try_for_each(|n_i| {
-                let threshold = fbas
-                    .graph
-                    .node_weight(n_i)
-                    .ok_or(FbasError::InternalError("Node index not found"))?
-                    .get_threshold();
-                let successors = fbas.graph.neighbors(n_i);
-                let comb_of_successors = successors.into_iter().combinations(threshold as usize);
-
-                let mut first_term = Vec::with_capacity(comb_of_successors.size_hint().0 + 1);
-                first_term.push(node_in_quorum(&n_i, false)?);
-                for pi_i in comb_of_successors {
-                    // Create a new variable as per Tseitin transformation for each
-                    // combination. These are internal variables for facilitation of
-                    // SAT solving. There is no need to store their indices.
-                    let alpha_i_j = Lit::new(self.solver.new_var_default(), true);
-                    // 1st term
-                    first_term.push(alpha_i_j);
-
-                    let mut third_term = Vec::with_capacity(pi_i.len() + 1);
-                    third_term.push(alpha_i_j);
-                    for n_k in pi_i.iter() {
-                        // 2nd term
-                        Self::add_clause_limited(
-                            &mut self.solver,
-                            &mut vec![!alpha_i_j, node_in_quorum(n_k, true)?],
-                        )?;
-                        // 3rd term
-                        third_term.push(node_in_quorum(n_k, false)?);
-                    }
-                    Self::add_clause_limited(&mut self.solver, &mut third_term)?;
+        // formula 3: qset relation for each vertex must be satisfied
+        Self::add_quorum_relation_clauses(
+            fbas,
+            &|ni, is_member| vars.lit_in_quorum_a(ni, is_member),
+            faulty,
+            sink,
+        )?;
+        Self::add_quorum_relation_clauses(
+            fbas,
+            &|ni, is_member| vars.lit_in_quorum_b(ni, is_member),
+            faulty,
+            sink,
+        )
+    }
+
+    // Formula 1 for a single quorum: at least one *validator* must exist in
+    // the quorum. Validators whose quorum set was assumed by a
+    // `MissingNodePolicy` don't count, as their vacuous quorum set would make
+    // them a quorum on their own.
+    pub(crate) fn add_non_empty_clauses<S: ClauseSink>(
+        fbas: &Fbas,
+        node_in_quorum: &dyn Fn(&NodeIndex) -> Result<Lit, FbasError>,
+        faulty: &BTreeMap<NodeIndex, Lit>,
+        sink: &mut S,
+    ) -> Result<(), FbasError> {
+        let known_validators = fbas
+            .validators
+            .iter()
+            .filter(|ni| !fbas.assumed.contains_key(ni))
+            .collect::<Vec<_>>();
+        let mut clause = Vec::with_capacity(known_validators.len());
+        for ni in known_validators.iter() {
+            let in_quorum = node_in_quorum(ni)?;
+            match faulty.get(ni) {
+                // an honest member: in the quorum and not faulty
+                Some(f) => {
+                    let honest = Lit::new(sink.new_var(), true);
+                    sink.add_clause(&mut vec![!honest, in_quorum])?;
+                    sink.add_clause(&mut vec![!honest, !*f])?;
+                    clause.push(honest);
                 }
-                Self::add_clause_limited(&mut self.solver, &mut first_term)?;
-                Ok(())
-            })
-        };
-        add_clauses_for_quorum_relations(&|ni, is_member| {
-            self.vars.lit_in_quorum_a(ni, is_member)
-        })?;
-        add_clauses_for_quorum_relations(&|ni, is_member| {
-            self.vars.lit_in_quorum_b(ni, is_member)
-        })?;
+                None => clause.push(in_quorum),
+            }
+        }
+        sink.add_clause(&mut clause)
+    }
 
-        trace!(
-            target: "SCP",
-            "FbasAnalyzer num_vars = {}, num_clauses = {}",
-            self.solver.num_vars(),
-            self.solver.num_clauses()
-        );
-        Ok(())
+    // Formula 3 for a single quorum: qset relation for each vertex must be
+    // satisfied. Variable naming follows "Final formula encoding that A and B
+    // are quorums" in `method.md`, assuming quorum A.
+    pub(crate) fn add_quorum_relation_clauses<S: ClauseSink>(
+        fbas: &Fbas,
+        node_in_quorum: &dyn Fn(&NodeIndex, bool) -> Result<Lit, FbasError>,
+        faulty: &BTreeMap<NodeIndex, Lit>,
+        sink: &mut S,
+    ) -> Result<(), FbasError> {
+        fbas.graph.node_indices().try_for_each(|n_i| {
+            let threshold = fbas
+                .graph
+                .node_weight(n_i)
+                .ok_or(FbasError::InternalError("Node index not found"))?
+                .get_threshold();
+            let successors = fbas.graph.neighbors(n_i);
+            let comb_of_successors = successors.into_iter().combinations(threshold as usize);
+
+            let mut first_term = Vec::with_capacity(comb_of_successors.size_hint().0 + 2);
+            first_term.push(node_in_quorum(&n_i, false)?);
+            // a faulty validator need not satisfy its quorum set
+            first_term.extend(faulty.get(&n_i));
+            for pi_i in comb_of_successors {
+                // Create a new variable as per Tseitin transformation for each
+                // combination. These are internal variables for facilitation of
+                // SAT solving. There is no need to store their indices.
+                let alpha_i_j = Lit::new(sink.new_var(), true);
+                // 1st term
+                first_term.push(alpha_i_j);
+
+                let mut third_term = Vec::with_capacity(pi_i.len() + 1);
+                third_term.push(alpha_i_j);
+                for n_k in pi_i.iter() {
+                    // 2nd term
+                    sink.add_clause(&mut vec![!alpha_i_j, node_in_quorum(n_k, true)?])?;
+                    // 3rd term
+                    third_term.push(node_in_quorum(n_k, false)?);
+                }
+                sink.add_clause(&mut third_term)?;
+            }
+            sink.add_clause(&mut first_term)?;
+            Ok(())
+        })
     }
 
     pub fn solve(&mut self) -> Result<SolveStatus, FbasError> {
@@ -275,39 +502,171 @@ impl FbasAnalyzer {
         // `SolveStatus::UNKNOWN`.
         // In order for the solver to return a `ResourcelimitExceeded` error, we need to
         // enforce the limit before returning.
-        let resource_limiter = self.solver.cb().clone();
-        let result = self.solver.solve_limited_th_full(&mut th, &[]);
-        self.status = match result {
-            SolveResult::Sat(model) => {
-                let mut quorum_a = vec![];
-                let mut quorum_b = vec![];
-                for ni in self.fbas.validators.iter() {
-                    let la = self.vars.lit_in_quorum_a(ni, true)?;
-                    if model.value_lit(la) == lbool::TRUE {
-                        quorum_a.push(*ni);
-                    }
-                    let lb = self.vars.lit_in_quorum_b(ni, true)?;
-                    if model.value_lit(lb) == lbool::TRUE {
-                        quorum_b.push(*ni);
+        let resource_limiter = self.solver.cb().limiter.clone();
+        resource_limiter.enter_phase(Phase::Solve);
+        let solve_start = resource_limiter.get_usage().time;
+        let mut propagations = self.solver.num_propagations();
+        self.status = loop {
+            let status = match self.solver.solve_limited_th_full(&mut th, &[]) {
+                SolveResult::Sat(model) => {
+                    let mut quorum_a = vec![];
+                    let mut quorum_b = vec![];
+                    for ni in self.fbas.validators.iter() {
+                        let la = self.vars.lit_in_quorum_a(ni, true)?;
+                        if model.value_lit(la) == lbool::TRUE {
+                            quorum_a.push(*ni);
+                        }
+                        let lb = self.vars.lit_in_quorum_b(ni, true)?;
+                        if model.value_lit(lb) == lbool::TRUE {
+                            quorum_b.push(*ni);
+                        }
                     }
+                    warn!(
+                        target: "SCP",
+                        "FbasAnalyzer found quorum split! quorum A: {:?}, quorum B: {:?}",
+                        quorum_a,
+                        quorum_b
+                    );
+                    SolveStatus::SAT((quorum_a, quorum_b))
                 }
-                warn!(
-                    target: "SCP",
-                    "FbasAnalyzer found quorum split! quorum A: {:?}, quorum B: {:?}",
-                    quorum_a,
-                    quorum_b
-                );
-                Ok(SolveStatus::SAT((quorum_a, quorum_b)))
+                SolveResult::Unsat(_) => SolveStatus::UNSAT,
+                // most likely the resource limits have been exceeded
+                SolveResult::Unknown(_) => SolveStatus::UNKNOWN,
+            };
+            resource_limiter.add_propagations(self.solver.num_propagations() - propagations);
+            propagations = self.solver.num_propagations();
+            // the solver may have been paused to check the propagation budget,
+            // in which case the search continues (keeping the learnt clauses)
+            if status != SolveStatus::UNKNOWN || !resource_limiter.resume_at_checkpoint() {
+                break status;
             }
-            SolveResult::Unsat(_) => Ok(SolveStatus::UNSAT),
-            // most likely the resource limits have been exceeded
-            SolveResult::Unknown(_) => Ok(SolveStatus::UNKNOWN),
-        }?;
+        };
+        resource_limiter.measure(false);
+        self.statistics.conflicts = self.solver.num_conflicts();
+        self.statistics.decisions = self.solver.num_decisions();
+        self.statistics.propagations = self.solver.num_propagations();
+        self.statistics.solve_time += resource_limiter
+            .get_usage()
+            .time
+            .saturating_sub(solve_start);
+        self.statistics.peak_mem_bytes = resource_limiter.get_peak_mem_bytes();
+        trace!(target: "SCP", "FbasAnalyzer statistics: {:?}", self.statistics);
         // enforce the limit (produce `Err(ResourcelimitExceeded)` if needed) before returning
         resource_limiter.measure_and_enforce_limits()?;
         Ok(self.status.clone())
     }
 
+    /// Returns the validators without a known quorum set, and the
+    /// `MissingNodePolicy` applied to each of them.
+    pub fn get_missing_nodes(&self) -> &[MissingNode] {
+        &self.fbas.missing
+    }
+
+    /// Returns the metadata of `validator`, if the input provided any
+    /// (currently only the stellarbeat JSON layout does).
+    pub fn get_node_metadata(&self, validator: &str) -> Option<&NodeMetadata> {
+        self.fbas.metadata.get(validator)
+    }
+
+    /// Returns the quorum sets that failed validation, see
+    /// `ParseOptions::strict`.
+    pub fn get_quorum_set_violations(&self) -> &[QuorumSetViolation] {
+        &self.fbas.violations
+    }
+
+    /// Returns the local node's view of the last SCP slot, if the input is
+    /// the output of stellar-core's `quorum` endpoint.
+    pub fn get_stellar_core_quorum_info(&self) -> Option<&StellarCoreQuorumInfo> {
+        self.fbas.quorum_info.as_ref()
+    }
+
+    /// Returns the organizations of an analyzer built by
+    /// `to_organization_analyzer` whose quorum sets had to be made stricter
+    /// than their validators' to collapse them.
+    pub fn get_approximate_organizations(&self) -> &[String] {
+        &self.fbas.approximate
+    }
+
+    /// Groups the validators into organizations.
+    pub fn get_organizations(&self, grouping: &OrganizationGrouping) -> Organizations {
+        Organizations::new(&self.fbas, grouping)
+    }
+
+    /// Builds an analyzer over the organizations of this FBAS, with each
+    /// organization collapsed into a single logical node named after it. Its
+    /// potential splits are pairs of organization quorums. An organization in
+    /// a quorum stands for all of its validators and requires the quorum sets
+    /// of all of them, so every split found is a split of the validators.
+    /// Conversely, every split that keeps organizations whole is found, unless
+    /// a quorum set weighs organizations unevenly and cannot be collapsed
+    /// exactly; `get_approximate_organizations` lists the organizations
+    /// concerned. Splits that divide an organization are not found.
+    pub fn to_organization_analyzer(
+        &self,
+        grouping: &OrganizationGrouping,
+        resource_limiter: ResourceLimiter,
+    ) -> Result<Self, FbasError> {
+        let organizations = self.get_organizations(grouping);
+        let fbas = self
+            .fbas
+            .collapse_organizations(&organizations, &resource_limiter)?;
+        Self::from_fbas(fbas, resource_limiter)
+    }
+
+    /// Builds an analyzer over the transitive closure of `validator`'s quorum
+    /// set, i.e. the network as `validator` sees it, like stellar-core's
+    /// analysis on its own node. The validators left out are listed by
+    /// `get_nodes_outside_closure`.
+    pub fn to_node_analyzer(
+        &self,
+        validator: &str,
+        resource_limiter: ResourceLimiter,
+    ) -> Result<Self, FbasError> {
+        let fbas = self
+            .fbas
+            .restrict_to_closure(validator, &resource_limiter)?;
+        Self::from_fbas(fbas, resource_limiter)
+    }
+
+    /// Returns the validators outside the transitive closure an analyzer
+    /// built by `to_node_analyzer` covers, empty for any other analyzer.
+    pub fn get_nodes_outside_closure(&self) -> &[String] {
+        &self.fbas.outside
+    }
+
+    pub(crate) fn fbas(&self) -> &Fbas {
+        &self.fbas
+    }
+
+    pub(crate) fn resource_limiter(&self) -> ResourceLimiter {
+        self.solver.cb().limiter.clone()
+    }
+
+    /// Returns statistics about the problem size and the solver's work. The
+    /// solver counters are filled in by `solve`, including when it fails due
+    /// to exceeded resource limits.
+    pub fn get_statistics(&self) -> &SolveStatistics {
+        &self.statistics
+    }
+
+    /// Writes the parsed quorum set map as JSON in the given layout. Nodes
+    /// whose quorum set is missing are left out.
+    #[cfg(any(feature = "json", test))]
+    pub fn to_json(&self, layout: crate::JsonLayout) -> String {
+        crate::json_writer::quorum_set_map_to_json(
+            &self.fbas.quorum_set_map,
+            &self.fbas.metadata,
+            layout,
+        )
+    }
+
+    /// Writes the parsed quorum set map as the paired node and quorum set XDR
+    /// buffers taken by `from_quorum_set_map_buf`. Fails if a validator is
+    /// not a valid public key strkey.
+    pub fn to_quorum_set_map_buf(&self) -> Result<QuorumSetMapBuf, FbasError> {
+        self.fbas.to_quorum_set_map_buf()
+    }
+
     pub fn get_potential_split(&self) -> Result<(Vec<String>, Vec<String>), FbasError> {
         match &self.status {
             // Note: the model returns one valid potential split, there is no
@@ -328,4 +687,46 @@ impl FbasAnalyzer {
             _ => Ok((vec![], vec![])),
         }
     }
+
+    /// Enables recording of a DRAT proof during `solve`. Must be called before
+    /// `solve`. If the result is `UNSAT`, the proof can be retrieved with
+    /// `get_drat_proof` and checked offline against `get_dimacs_formula`.
+    pub fn enable_proof_logging(&mut self) {
+        self.solver.cb_mut().enable_proof();
+    }
+
+    /// Returns the formula handed to the solver in DIMACS CNF format. The
+    /// variable numbering matches the one used in the DRAT proof.
+    pub fn get_dimacs_formula(&self) -> Result<String, FbasError> {
+        let mut formula = DimacsFormula::with_vars(2 * self.fbas.graph.node_count() as u32);
+        Self::add_formula_clauses(&self.fbas, &self.vars, &mut formula)?;
+        if formula.num_vars() != self.solver.num_vars() {
+            return Err(FbasError::InternalError(
+                "replayed formula variables count does not match the solver's",
+            ));
+        }
+        trace!(
+            target: "SCP",
+            "FbasAnalyzer replayed formula: num_vars = {}, num_clauses = {}",
+            formula.num_vars(),
+            formula.num_clauses()
+        );
+        Ok(formula.to_dimacs())
+    }
+
+    /// Returns the DRAT proof of the `UNSAT` result, which certifies that all
+    /// quorums intersect.
+    pub fn get_drat_proof(&self) -> Result<String, FbasError> {
+        if self.status != SolveStatus::UNSAT {
+            return Err(FbasError::ProofUnavailable(
+                "a proof only exists for an UNSAT result",
+            ));
+        }
+        match self.solver.cb().proof() {
+            Some(proof) => Ok(proof.to_string()),
+            None => Err(FbasError::ProofUnavailable(
+                "proof logging was not enabled before solving",
+            )),
+        }
+    }
 }
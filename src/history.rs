@@ -0,0 +1,122 @@
+use crate::{
+    fbas::{node_id_to_string, ErrorLocation, FbasError, QuorumSetMap},
+    resource_limiter::ResourceLimiter,
+};
+use log::{trace, warn};
+use sha2::{Digest, Sha256};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fs::File,
+    io::BufReader,
+    rc::Rc,
+};
+use stellar_xdr::curr::{
+    Frame, Hash, Limited, Limits, ReadXdr, ScpHistoryEntry, ScpQuorumSet, ScpStatementPledges,
+    WriteXdr,
+};
+
+// History archives store, for every checkpoint, a `scp-*.xdr` file: a stream
+// of `ScpHistoryEntry` records, each preceded by an RFC 5531 record mark. An
+// entry holds the SCP messages of one ledger and the quorum sets they refer
+// to. A message only carries the hash of its sender's quorum set, so quorum
+// sets are collected from all entries and resolved by hash.
+//
+// The files are untrusted input, so decoding is bounded: the XDR nesting depth
+// by `XDR_DEPTH_LIMIT`, and the number of bytes decoded by the size of the
+// file, so that a forged length prefix fails instead of allocating.
+
+const XDR_DEPTH_LIMIT: u32 = 200;
+
+pub(crate) fn quorum_set_hash(qset: &ScpQuorumSet) -> Result<Hash, FbasError> {
+    let xdr = qset
+        .to_xdr(Limits::none())
+        .map_err(|_| FbasError::InternalError("ScpQuorumSet cannot be encoded to xdr"))?;
+    Ok(Hash(Sha256::digest(xdr).into()))
+}
+
+fn pledged_quorum_set_hash(pledges: &ScpStatementPledges) -> &Hash {
+    match pledges {
+        ScpStatementPledges::Prepare(prepare) => &prepare.quorum_set_hash,
+        ScpStatementPledges::Confirm(confirm) => &confirm.quorum_set_hash,
+        ScpStatementPledges::Externalize(externalize) => &externalize.commit_quorum_set_hash,
+        ScpStatementPledges::Nominate(nomination) => &nomination.quorum_set_hash,
+    }
+}
+
+/// Reads SCP history files (uncompressed `scp-*.xdr`), and returns the latest
+/// quorum set of every node that sent a message in a ledger up to
+/// `max_ledger`. Nodes whose quorum set hash can't be resolved are returned
+/// separately.
+pub(crate) fn quorum_set_map_from_scp_history<P: AsRef<str>>(
+    paths: &[P],
+    max_ledger: Option<u32>,
+    resource_limiter: &ResourceLimiter,
+) -> Result<(QuorumSetMap, BTreeSet<String>), FbasError> {
+    let mut quorum_sets = BTreeMap::new();
+    // node -> (ledger, quorum set hash)
+    let mut latest: BTreeMap<String, (u32, Hash)> = BTreeMap::new();
+
+    for path in paths.iter() {
+        let path = path.as_ref();
+        let location = |index: Option<usize>| {
+            Box::new(ErrorLocation {
+                file: Some(path.to_string()),
+                buffer_index: index,
+                ..Default::default()
+            })
+        };
+        let file = File::open(path)
+            .map_err(|_| FbasError::ParseError("fail to open file", location(None)))?;
+        let size = file
+            .metadata()
+            .map_err(|_| FbasError::ParseError("fail to read file", location(None)))?
+            .len();
+        let limits = Limits {
+            depth: XDR_DEPTH_LIMIT,
+            len: usize::try_from(size).unwrap_or(usize::MAX),
+        };
+        let mut reader = Limited::new(BufReader::new(file), limits);
+        for (i, entry) in Frame::<ScpHistoryEntry>::read_xdr_iter(&mut reader).enumerate() {
+            resource_limiter.measure_and_enforce_limits()?;
+            let Frame(ScpHistoryEntry::V0(entry)) = entry.map_err(|err| {
+                FbasError::XdrDecodingError(
+                    "ScpHistoryEntry cannot be decoded from xdr",
+                    location(Some(i)),
+                    err,
+                )
+            })?;
+            let ledger = entry.ledger_messages.ledger_seq;
+            if max_ledger.is_some_and(|max_ledger| ledger > max_ledger) {
+                continue;
+            }
+            for qset in entry.quorum_sets.iter() {
+                quorum_sets.insert(quorum_set_hash(qset)?, qset.clone());
+            }
+            for envelope in entry.ledger_messages.messages.iter() {
+                let node = node_id_to_string(&envelope.statement.node_id);
+                let hash = pledged_quorum_set_hash(&envelope.statement.pledges);
+                if latest.get(&node).is_none_or(|(seen, _)| ledger >= *seen) {
+                    latest.insert(node, (ledger, hash.clone()));
+                }
+            }
+        }
+    }
+
+    let mut quorum_map = QuorumSetMap::new();
+    let mut unresolved = BTreeSet::new();
+    for (node, (ledger, hash)) in latest.into_iter() {
+        match quorum_sets.get(&hash) {
+            Some(qset) => {
+                trace!(target: "SCP", "quorum set of {} as of ledger {}", node, ledger);
+                quorum_map.insert(node, Rc::new(qset.clone().into()));
+            }
+            None => {
+                warn!(target: "SCP",
+                    "quorum set of {} used in ledger {} is not in the history", node, ledger
+                );
+                unresolved.insert(node);
+            }
+        }
+    }
+    Ok((quorum_map, unresolved))
+}
@@ -0,0 +1,101 @@
+use crate::{
+    cardinality::{at_least, at_most_assumption},
+    fbas::{FbasError, MissingNodePolicy},
+    fbas_analyze::{solve_resumable, FbasAnalyzer, LimitedSolver, VarManager},
+    proof::{AnalyzerCallbacks, ClauseSink},
+    resource_limiter::Phase,
+    split_constraints::QuorumPair,
+};
+use batsat::{Lit, Solver};
+use petgraph::graph::NodeIndex;
+use std::collections::{BTreeMap, BTreeSet};
+
+// Finds the smallest intersection of two quorums with the analyzer's formula,
+// relaxed so that validators may be in both quorums if marked as shared. Any
+// pair of quorums is a solution, and the shared validators are counted by a
+// cardinality constraint. Starting from the intersection of the first pair
+// found, the bound is lowered to one less than the latest intersection until
+// no pair is left, so the last pair has the smallest intersection. A size of
+// zero means the quorums are disjoint, i.e. intersection does not hold.
+// Validators assumed Byzantine by a `MissingNodePolicy` can be in both
+// quorums anyway and are not counted.
+
+/// The smallest intersection of two quorums, with a pair of quorums that
+/// intersect in it.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct QuorumIntersection {
+    pub intersection: Vec<String>,
+    pub quorums: QuorumPair,
+}
+
+impl FbasAnalyzer {
+    /// Finds the smallest number of validators two quorums can share, which
+    /// is the margin by which the network enjoys quorum intersection. Returns
+    /// `None` if there is no quorum. The search shares the analyzer's
+    /// `ResourceLimiter`, but not its solver.
+    pub fn minimum_quorum_intersection(&self) -> Result<Option<QuorumIntersection>, FbasError> {
+        let fbas = self.fbas();
+        let limiter = self.resource_limiter();
+        limiter.enter_phase(Phase::Formula);
+        let mut solver = Solver::new(Default::default(), AnalyzerCallbacks::new(limiter.clone()));
+        let mut sink = LimitedSolver(&mut solver);
+        let vars = VarManager::new(fbas, &mut sink);
+        let shared = fbas
+            .validators
+            .iter()
+            .filter(|ni| fbas.assumed.get(ni) != Some(&MissingNodePolicy::Byzantine))
+            .map(|ni| (*ni, Lit::new(sink.new_var(), true)))
+            .collect::<BTreeMap<_, _>>();
+        Self::add_formula_clauses_with_faults(fbas, &vars, &BTreeMap::new(), &shared, &mut sink)?;
+        limiter.enter_phase(Phase::Solve);
+
+        let pair = |solver: &mut Solver<AnalyzerCallbacks>, assumptions: &[Lit]| {
+            solve_resumable(solver, assumptions, |value| {
+                let (mut quorum_a, mut quorum_b) = (BTreeSet::new(), BTreeSet::new());
+                for ni in fbas.validators.iter() {
+                    if vars.lit_in_quorum_a(ni, true).is_ok_and(value) {
+                        quorum_a.insert(*ni);
+                    }
+                    if vars.lit_in_quorum_b(ni, true).is_ok_and(value) {
+                        quorum_b.insert(*ni);
+                    }
+                }
+                (quorum_a, quorum_b)
+            })
+        };
+        let Some(mut best) = pair(&mut solver, &[])? else {
+            return Ok(None);
+        };
+        let shared_by = |(a, b): &(BTreeSet<NodeIndex>, BTreeSet<NodeIndex>)| {
+            a.intersection(b)
+                .filter(|ni| shared.contains_key(ni))
+                .copied()
+                .collect::<BTreeSet<_>>()
+        };
+        let size = |pair: &_| shared_by(pair).len();
+        let lits = shared.values().copied().collect::<Vec<_>>();
+        let outputs = at_least(&mut LimitedSolver(&mut solver), &lits, size(&best))?;
+        while let Some(k) = size(&best).checked_sub(1) {
+            let assumptions = at_most_assumption(&outputs, k)
+                .into_iter()
+                .collect::<Vec<_>>();
+            match pair(&mut solver, &assumptions)? {
+                Some(smaller) => best = smaller,
+                None => break,
+            }
+        }
+
+        let names = |quorum: &BTreeSet<NodeIndex>| {
+            quorum
+                .iter()
+                .map(|ni| fbas.try_get_validator_string(ni))
+                .collect::<Result<Vec<_>, _>>()
+        };
+        let intersection = shared_by(&best);
+        let (quorum_a, quorum_b) = best;
+        Ok(Some(QuorumIntersection {
+            intersection: names(&intersection)?,
+            quorums: (names(&quorum_a)?, names(&quorum_b)?),
+        }))
+    }
+}
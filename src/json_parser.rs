@@ -1,74 +1,183 @@
-use crate::fbas::{FbasError, InternalScpQuorumSet, QuorumSetMap};
+use crate::fbas::{
+    ErrorLocation, FbasError, InternalScpQuorumSet, NodeMetadata, NodeMetadataMap, QuorumSetMap,
+    StellarCoreQuorumInfo,
+};
 use json::{object::Object, JsonValue};
-use std::{fs::File, io::Read, rc::Rc};
+use log::warn;
+use std::{collections::BTreeSet, fs::File, io::Read, rc::Rc};
 
+// Tracks the position in the document so that errors can point at the
+// offending value.
+struct Context<'a> {
+    file: &'a str,
+    node: Option<String>,
+}
+
+impl Context<'_> {
+    fn error(&self, msg: &'static str, pointer: &str, qset_path: &[usize]) -> FbasError {
+        FbasError::ParseError(
+            msg,
+            Box::new(ErrorLocation {
+                file: Some(self.file.to_string()),
+                json_pointer: Some(pointer.to_string()),
+                node: self.node.clone(),
+                qset_path: qset_path.to_vec(),
+                buffer_index: None,
+            }),
+        )
+    }
+}
+
+/// Everything read from a JSON input.
+#[derive(Debug, Default)]
+pub(crate) struct JsonInput {
+    pub quorum_set_map: QuorumSetMap,
+    /// Only the stellarbeat layout carries node metadata.
+    pub metadata: NodeMetadataMap,
+    /// Nodes the input explicitly reports without a quorum set.
+    pub missing_qsets: BTreeSet<String>,
+    /// Only stellar-core's quorum endpoint reports the local node's slot.
+    pub quorum_info: Option<StellarCoreQuorumInfo>,
+}
+
+#[cfg(test)]
 pub(crate) fn quorum_set_map_from_json(path: &str) -> Result<QuorumSetMap, FbasError> {
-    let mut file = File::open(path).map_err(|_| FbasError::ParseError("fail to open file"))?;
+    parse_json(path).map(|input| input.quorum_set_map)
+}
+
+#[cfg(test)]
+pub(crate) fn quorum_set_map_and_metadata_from_json(
+    path: &str,
+) -> Result<(QuorumSetMap, NodeMetadataMap), FbasError> {
+    parse_json(path).map(|input| (input.quorum_set_map, input.metadata))
+}
+
+/// Parses a JSON input in any of the supported layouts.
+pub(crate) fn parse_json(path: &str) -> Result<JsonInput, FbasError> {
+    let ctx = Context {
+        file: path,
+        node: None,
+    };
+    let mut file = File::open(path).map_err(|_| ctx.error("fail to open file", "", &[]))?;
     let mut data = String::new();
     file.read_to_string(&mut data)
-        .map_err(|_| FbasError::ParseError("fail to read file"))?;
-    let json_data =
-        json::parse(&data).map_err(|_| FbasError::ParseError("fail to parse to json"))?;
+        .map_err(|_| ctx.error("fail to read file", "", &[]))?;
+    let json_data = json::parse(&data).map_err(|_| ctx.error("fail to parse to json", "", &[]))?;
 
     match json_data {
-        JsonValue::Object(root) => try_parse_quorum_set_map_from_json_regular(root),
-        JsonValue::Array(nodes) => try_parse_quorum_set_map_from_stellarbeats_json(nodes),
-        _ => Err(FbasError::ParseError(
-            "root is neither an object nor an array",
-        )),
+        JsonValue::Object(root) if root.get("transitive").is_some() => {
+            try_parse_quorum_set_map_from_stellar_core_json(root, ctx)
+        }
+        JsonValue::Object(root) => {
+            try_parse_quorum_set_map_from_json_regular(root, ctx).map(|quorum_set_map| JsonInput {
+                quorum_set_map,
+                ..Default::default()
+            })
+        }
+        JsonValue::Array(nodes) => try_parse_quorum_set_map_from_stellarbeats_json(nodes, ctx).map(
+            |(quorum_set_map, metadata)| JsonInput {
+                quorum_set_map,
+                metadata,
+                ..Default::default()
+            },
+        ),
+        _ => Err(ctx.error("root is neither an object nor an array", "", &[])),
     }
 }
 
-fn try_parse_quorum_set_map_from_json_regular(root: Object) -> Result<QuorumSetMap, FbasError> {
+fn try_parse_quorum_set_map_from_json_regular(
+    root: Object,
+    mut ctx: Context,
+) -> Result<QuorumSetMap, FbasError> {
     let nodes = match root.get("nodes") {
         Some(JsonValue::Array(nodes)) => nodes,
-        _ => return Err(FbasError::ParseError("nodes field missing or not an array")),
+        _ => return Err(ctx.error("nodes field missing or not an array", "/nodes", &[])),
     };
 
     let mut quorum_map = QuorumSetMap::new();
-    for node in nodes {
+    for (i, node) in nodes.iter().enumerate() {
+        let pointer = format!("/nodes/{i}");
+        ctx.node = None;
         let node = match node {
             JsonValue::Object(n) => n,
-            _ => return Err(FbasError::ParseError("node is not an object")),
+            _ => return Err(ctx.error("node is not an object", &pointer, &[])),
         };
 
         let public_key = node
             .get("node")
             .and_then(|n| n.as_str())
-            .ok_or(FbasError::ParseError("node field missing or not a string"))?
+            .ok_or_else(|| {
+                ctx.error(
+                    "node field missing or not a string",
+                    &format!("{pointer}/node"),
+                    &[],
+                )
+            })?
             .to_string();
+        ctx.node = Some(public_key.clone());
 
-        let qset = parse_internal_quorum_set(&node["qset"])?;
+        let qset = parse_internal_quorum_set(
+            &node["qset"],
+            &ctx,
+            &format!("{pointer}/qset"),
+            &mut vec![],
+        )?;
         quorum_map.insert(public_key, Rc::new(qset));
     }
 
     Ok(quorum_map)
 }
 
-fn parse_internal_quorum_set(json_qset: &JsonValue) -> Result<InternalScpQuorumSet, FbasError> {
-    let threshold = json_qset["t"].as_u32().ok_or(FbasError::ParseError(
-        "threshold field missing or not a number",
-    ))?;
+fn parse_internal_quorum_set(
+    json_qset: &JsonValue,
+    ctx: &Context,
+    pointer: &str,
+    qset_path: &mut Vec<usize>,
+) -> Result<InternalScpQuorumSet, FbasError> {
+    let threshold = json_qset["t"].as_u32().ok_or_else(|| {
+        ctx.error(
+            "threshold field missing or not a number",
+            &format!("{pointer}/t"),
+            qset_path,
+        )
+    })?;
 
     let v = match &json_qset["v"] {
         JsonValue::Array(v) => v,
-        _ => return Err(FbasError::ParseError("v field missing or not an array")),
+        _ => {
+            return Err(ctx.error(
+                "v field missing or not an array",
+                &format!("{pointer}/v"),
+                qset_path,
+            ))
+        }
     };
 
     let mut validators = vec![];
     let mut inner_sets = vec![];
 
-    for item in v {
+    for (i, item) in v.iter().enumerate() {
+        let item_pointer = format!("{pointer}/v/{i}");
         match item {
-            JsonValue::String(validator) => {
-                validators.push(validator.to_string());
+            // short strings are parsed as `JsonValue::Short`
+            JsonValue::String(_) | JsonValue::Short(_) => {
+                validators.push(item.to_string());
             }
             JsonValue::Object(obj) if obj.get("t").is_some() => {
-                inner_sets.push(parse_internal_quorum_set(item)?);
+                qset_path.push(inner_sets.len());
+                inner_sets.push(parse_internal_quorum_set(
+                    item,
+                    ctx,
+                    &item_pointer,
+                    qset_path,
+                )?);
+                qset_path.pop();
             }
             _ => {
-                return Err(FbasError::ParseError(
+                return Err(ctx.error(
                     "validator entry must be either a string (PublicKey) or an object (QuorumSet)",
+                    &item_pointer,
+                    qset_path,
                 ))
             }
         }
@@ -83,41 +192,63 @@ fn parse_internal_quorum_set(json_qset: &JsonValue) -> Result<InternalScpQuorumS
 
 fn parse_stellarbeats_internal_quorum_set(
     json_qset: &JsonValue,
+    ctx: &Context,
+    pointer: &str,
+    qset_path: &mut Vec<usize>,
 ) -> Result<InternalScpQuorumSet, FbasError> {
-    let threshold = json_qset["threshold"]
-        .as_u32()
-        .ok_or(FbasError::ParseError(
+    let threshold = json_qset["threshold"].as_u32().ok_or_else(|| {
+        ctx.error(
             "threshold field missing or not a number",
-        ))?;
+            &format!("{pointer}/threshold"),
+            qset_path,
+        )
+    })?;
 
     let mut validators = vec![];
     let mut inner_sets = vec![];
 
     match &json_qset["validators"] {
         JsonValue::Array(validator_arr) => {
-            for validator in validator_arr {
+            for (i, validator) in validator_arr.iter().enumerate() {
                 match validator.as_str() {
                     Some(validator_str) => validators.push(validator_str.to_string()),
-                    None => return Err(FbasError::ParseError("validator entry must be a string")),
+                    None => {
+                        return Err(ctx.error(
+                            "validator entry must be a string",
+                            &format!("{pointer}/validators/{i}"),
+                            qset_path,
+                        ))
+                    }
                 }
             }
         }
         _ => {
-            return Err(FbasError::ParseError(
+            return Err(ctx.error(
                 "validators field missing or not an array",
+                &format!("{pointer}/validators"),
+                qset_path,
             ))
         }
     }
 
     match &json_qset["innerQuorumSets"] {
         JsonValue::Array(inner_arr) => {
-            for inner_qset in inner_arr {
-                inner_sets.push(parse_stellarbeats_internal_quorum_set(inner_qset)?);
+            for (i, inner_qset) in inner_arr.iter().enumerate() {
+                qset_path.push(i);
+                inner_sets.push(parse_stellarbeats_internal_quorum_set(
+                    inner_qset,
+                    ctx,
+                    &format!("{pointer}/innerQuorumSets/{i}"),
+                    qset_path,
+                )?);
+                qset_path.pop();
             }
         }
         _ => {
-            return Err(FbasError::ParseError(
+            return Err(ctx.error(
                 "innerQuorumSets field missing or not an array",
+                &format!("{pointer}/innerQuorumSets"),
+                qset_path,
             ))
         }
     }
@@ -129,27 +260,224 @@ fn parse_stellarbeats_internal_quorum_set(
     })
 }
 
+fn parse_stellarbeats_node_metadata(
+    node: &JsonValue,
+    ctx: &Context,
+    pointer: &str,
+) -> Result<NodeMetadata, FbasError> {
+    // stellarbeat reports unknown values as null
+    let optional_str = |field: &str| match &node[field] {
+        JsonValue::Null => Ok(None),
+        value => value.as_str().map(|s| Some(s.to_string())).ok_or_else(|| {
+            ctx.error(
+                "metadata field is not a string",
+                &format!("{pointer}/{field}"),
+                &[],
+            )
+        }),
+    };
+    let optional_bool = |field: &str| match &node[field] {
+        JsonValue::Null => Ok(None),
+        value => value.as_bool().map(Some).ok_or_else(|| {
+            ctx.error(
+                "metadata field is not a boolean",
+                &format!("{pointer}/{field}"),
+                &[],
+            )
+        }),
+    };
+    Ok(NodeMetadata {
+        name: optional_str("name")?,
+        home_domain: optional_str("homeDomain")?,
+        isp: optional_str("isp")?,
+        organization_id: optional_str("organizationId")?,
+        active: optional_bool("active")?,
+        is_validating: optional_bool("isValidating")?,
+    })
+}
+
 fn try_parse_quorum_set_map_from_stellarbeats_json(
     nodes: Vec<JsonValue>,
-) -> Result<QuorumSetMap, FbasError> {
+    mut ctx: Context,
+) -> Result<(QuorumSetMap, NodeMetadataMap), FbasError> {
     let mut quorum_map = QuorumSetMap::new();
-    for node in nodes {
+    let mut metadata = NodeMetadataMap::new();
+    for (i, node) in nodes.iter().enumerate() {
+        let pointer = format!("/{i}");
+        ctx.node = None;
         let node = match node {
             JsonValue::Object(n) => n,
-            _ => return Err(FbasError::ParseError("node is not an object")),
+            _ => return Err(ctx.error("node is not an object", &pointer, &[])),
         };
 
         let public_key = node
             .get("publicKey")
             .and_then(|n| n.as_str())
-            .ok_or(FbasError::ParseError(
-                "publicKey field missing or not a string",
-            ))?
+            .ok_or_else(|| {
+                ctx.error(
+                    "publicKey field missing or not a string",
+                    &format!("{pointer}/publicKey"),
+                    &[],
+                )
+            })?
+            .to_string();
+        ctx.node = Some(public_key.clone());
+
+        let qset = parse_stellarbeats_internal_quorum_set(
+            &node["quorumSet"],
+            &ctx,
+            &format!("{pointer}/quorumSet"),
+            &mut vec![],
+        )?;
+        let meta = parse_stellarbeats_node_metadata(&nodes[i], &ctx, &pointer)?;
+        if meta != NodeMetadata::default() {
+            metadata.insert(public_key.clone(), meta);
+        }
+        quorum_map.insert(public_key, Rc::new(qset));
+    }
+
+    Ok((quorum_map, metadata))
+}
+
+fn parse_slot_quorum_info(
+    node: String,
+    json_info: &JsonValue,
+    ctx: &Context,
+    pointer: &str,
+) -> Result<StellarCoreQuorumInfo, FbasError> {
+    let optional_u32 = |field: &str| match &json_info[field] {
+        JsonValue::Null => Ok(None),
+        value => value.as_u32().map(Some).ok_or_else(|| {
+            ctx.error(
+                "quorum info count is not a number",
+                &format!("{pointer}/{field}"),
+                &[],
+            )
+        }),
+    };
+    let phase = match &json_info["phase"] {
+        JsonValue::Null => None,
+        value => Some(
+            value
+                .as_str()
+                .ok_or_else(|| {
+                    ctx.error("phase is not a string", &format!("{pointer}/phase"), &[])
+                })?
+                .to_string(),
+        ),
+    };
+    let missing = match &json_info["missing"] {
+        JsonValue::Null => vec![],
+        JsonValue::Array(nodes) => nodes
+            .iter()
+            .enumerate()
+            .map(|(i, node)| {
+                node.as_str().map(str::to_string).ok_or_else(|| {
+                    ctx.error(
+                        "missing entry must be a string",
+                        &format!("{pointer}/missing/{i}"),
+                        &[],
+                    )
+                })
+            })
+            .collect::<Result<_, _>>()?,
+        _ => {
+            return Err(ctx.error(
+                "missing field is not an array",
+                &format!("{pointer}/missing"),
+                &[],
+            ))
+        }
+    };
+    Ok(StellarCoreQuorumInfo {
+        node,
+        phase,
+        agree: optional_u32("agree")?,
+        disagree: optional_u32("disagree")?,
+        missing,
+    })
+}
+
+// Parses the output of stellar-core's `quorum?transitive=true` endpoint: the
+// local node with its slot info (whose `value` is its quorum set), and the
+// transitive quorum, where every node's quorum set is in the same `{t, v}`
+// layout as the regular format. Nodes whose quorum set stellar-core has not
+// heard of have no `qset`, and are reported as missing quorum sets.
+fn try_parse_quorum_set_map_from_stellar_core_json(
+    root: Object,
+    mut ctx: Context,
+) -> Result<JsonInput, FbasError> {
+    let nodes = match &root["transitive"]["nodes"] {
+        JsonValue::Array(nodes) => nodes,
+        _ => {
+            return Err(ctx.error(
+                "transitive.nodes field missing or not an array",
+                "/transitive/nodes",
+                &[],
+            ))
+        }
+    };
+
+    let mut quorum_map = QuorumSetMap::new();
+    let mut missing_qsets = BTreeSet::new();
+    for (i, node) in nodes.iter().enumerate() {
+        let pointer = format!("/transitive/nodes/{i}");
+        ctx.node = None;
+        let public_key = node["node"]
+            .as_str()
+            .ok_or_else(|| {
+                ctx.error(
+                    "node field missing or not a string",
+                    &format!("{pointer}/node"),
+                    &[],
+                )
+            })?
             .to_string();
+        ctx.node = Some(public_key.clone());
 
-        let qset = parse_stellarbeats_internal_quorum_set(&node["quorumSet"])?;
+        if node["qset"].is_null() {
+            warn!(target: "SCP", "quorum set of {} is not known to stellar-core", public_key);
+            missing_qsets.insert(public_key);
+            continue;
+        }
+        let qset = parse_internal_quorum_set(
+            &node["qset"],
+            &ctx,
+            &format!("{pointer}/qset"),
+            &mut vec![],
+        )?;
         quorum_map.insert(public_key, Rc::new(qset));
     }
 
-    Ok(quorum_map)
+    // the local node is part of its own transitive quorum, but its quorum set
+    // may only be reported in the slot info
+    let mut quorum_info = None;
+    ctx.node = root
+        .get("node")
+        .and_then(|n| n.as_str())
+        .map(str::to_string);
+    if let Some(local) = ctx.node.clone() {
+        quorum_info = Some(parse_slot_quorum_info(
+            local.clone(),
+            &root["qset"],
+            &ctx,
+            "/qset",
+        )?);
+        if !quorum_map.contains_key(&local) && !root["qset"]["value"].is_null() {
+            let qset = parse_internal_quorum_set(
+                &root["qset"]["value"],
+                &ctx,
+                "/qset/value",
+                &mut vec![],
+            )?;
+            quorum_map.insert(local, Rc::new(qset));
+        }
+    }
+
+    Ok(JsonInput {
+        quorum_set_map: quorum_map,
+        missing_qsets,
+        quorum_info,
+        ..Default::default()
+    })
 }
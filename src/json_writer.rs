@@ -0,0 +1,69 @@
+use crate::fbas::{InternalScpQuorumSet, NodeMetadataMap, QuorumSetMap};
+use json::{object, JsonValue};
+
+/// The JSON layouts a quorum set map can be written in. Both are read back by
+/// `from_json_path`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum JsonLayout {
+    /// `{"nodes": [{"node", "qset": {"t", "v"}}]}`, where `v` mixes
+    /// validators and inner quorum sets, as used by stellar-core's tests.
+    Regular,
+    /// An array of `{"publicKey", "quorumSet": {"threshold", "validators",
+    /// "innerQuorumSets"}}`, as served by stellarbeat, including the known
+    /// node metadata.
+    Stellarbeat,
+}
+
+fn regular_quorum_set(qset: &InternalScpQuorumSet) -> JsonValue {
+    let mut v: Vec<JsonValue> = qset.validators.iter().map(|v| v.as_str().into()).collect();
+    v.extend(qset.inner_sets.iter().map(regular_quorum_set));
+    object! { t: qset.threshold, v: v }
+}
+
+fn stellarbeat_quorum_set(qset: &InternalScpQuorumSet) -> JsonValue {
+    let validators: Vec<JsonValue> = qset.validators.iter().map(|v| v.as_str().into()).collect();
+    let inner_sets: Vec<JsonValue> = qset.inner_sets.iter().map(stellarbeat_quorum_set).collect();
+    object! {
+        threshold: qset.threshold,
+        validators: validators,
+        innerQuorumSets: inner_sets,
+    }
+}
+
+pub(crate) fn quorum_set_map_to_json(
+    quorum_map: &QuorumSetMap,
+    metadata: &NodeMetadataMap,
+    layout: JsonLayout,
+) -> String {
+    let root = match layout {
+        JsonLayout::Regular => {
+            let nodes: Vec<JsonValue> = quorum_map
+                .iter()
+                .map(|(node, qset)| object! { node: node.as_str(), qset: regular_quorum_set(qset) })
+                .collect();
+            object! { nodes: nodes }
+        }
+        JsonLayout::Stellarbeat => JsonValue::Array(
+            quorum_map
+                .iter()
+                .map(|(node, qset)| {
+                    let mut entry = object! {
+                        publicKey: node.as_str(),
+                        quorumSet: stellarbeat_quorum_set(qset),
+                    };
+                    if let Some(meta) = metadata.get(node) {
+                        // unknown fields are written as null, like stellarbeat does
+                        let _ = entry.insert("name", meta.name.clone());
+                        let _ = entry.insert("homeDomain", meta.home_domain.clone());
+                        let _ = entry.insert("isp", meta.isp.clone());
+                        let _ = entry.insert("organizationId", meta.organization_id.clone());
+                        let _ = entry.insert("active", meta.active);
+                        let _ = entry.insert("isValidating", meta.is_validating);
+                    }
+                    entry
+                })
+                .collect(),
+        ),
+    };
+    json::stringify_pretty(root, 4)
+}
@@ -1,16 +1,54 @@
 mod allocator;
+pub(crate) mod cardinality;
+pub(crate) mod fault_sets;
 pub(crate) mod fbas;
 pub(crate) mod fbas_analyze;
+pub(crate) mod history;
+pub(crate) mod intersection;
+pub(crate) mod lints;
+pub(crate) mod meter;
+pub(crate) mod minimal_quorums;
+pub(crate) mod organizations;
+pub(crate) mod proof;
+pub(crate) mod quorums;
 pub(crate) mod resource_limiter;
+pub(crate) mod split_constraints;
+pub(crate) mod tolerance;
+pub(crate) mod validation;
 
 #[cfg(any(feature = "json", test))]
 pub(crate) mod json_parser;
 
+#[cfg(any(feature = "json", test))]
+pub(crate) mod json_writer;
+
+#[cfg(any(feature = "toml", test))]
+pub(crate) mod toml_parser;
+
 #[cfg(test)]
 mod test;
 
+pub use allocator::LimitedAllocator;
 pub use batsat::callbacks::{AsyncInterrupt, AsyncInterruptHandle, Basic, Callbacks};
-pub use fbas::FbasError;
-pub use fbas_analyze::{FbasAnalyzer, SolveStatus};
-pub use resource_limiter::{ResourceLimiter, ResourceQuantity};
+pub use fault_sets::{OrganizationFaultSet, OrganizationFaults};
+pub use fbas::{
+    ErrorLocation, FbasError, MissingNode, MissingNodePolicy, MissingReason, NodeMetadata,
+    ParseOptions, QuorumSetMapBuf, StellarCoreQuorumInfo,
+};
+pub use fbas_analyze::{FbasAnalyzer, SolveStatistics, SolveStatus};
+pub use intersection::QuorumIntersection;
+#[cfg(any(feature = "json", test))]
+pub use json_writer::JsonLayout;
+pub use lints::{Lint, LintKind, Severity};
+pub use meter::{
+    AllocatorMeter, Clock, MemoryMeter, MockClock, MockMeter, ProcRssMeter, SystemClock,
+};
+pub use minimal_quorums::MinimalQuorums;
+pub use organizations::{OrganizationGrouping, Organizations};
+pub use resource_limiter::{
+    Phase, ResourceKind, ResourceLimiter, ResourceQuantity, SolveProgress, WorkLimits,
+};
+pub use split_constraints::{QuorumPair, SplitConstraints};
 pub use stellar_xdr as xdr;
+pub use tolerance::{FaultTolerance, QuorumSetFaultTolerance};
+pub use validation::{validate_quorum_set, QuorumSetViolation, ViolationKind};
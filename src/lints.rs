@@ -0,0 +1,246 @@
+use crate::{
+    fbas::{Fbas, InternalScpQuorumSet, MissingReason},
+    fbas_analyze::FbasAnalyzer,
+    organizations::Organizations,
+};
+use std::collections::{BTreeMap, BTreeSet};
+
+// Lints flag quorum set configurations that are legal, and may well keep
+// quorum intersection, but are common sources of trouble. They look at each
+// quorum set on its own (or at pairs of them), so unlike the SAT analysis
+// they neither prove nor rule out a split.
+//
+// Several lints only apply to the trust core: the validators that are trusted
+// by another validator of the core. It is found as a fixpoint, starting from
+// all validators and dropping the ones no remaining validator trusts, which
+// leaves out watchers and other validators nobody depends on.
+
+/// Organizations with fewer validators than this cannot keep a 2-of-3 inner
+/// set through the loss of one validator.
+const MIN_ORGANIZATION_SIZE: usize = 3;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    Info,
+    Warning,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum LintKind {
+    /// The validator's organization has fewer than three validators.
+    SmallOrganization { organization: String, size: usize },
+    /// An inner set with more than one entry has threshold 1, so a single
+    /// Byzantine entry satisfies it.
+    InnerThresholdOne { path: Vec<usize>, entries: usize },
+    /// The validator trusts `trusted`, which does not trust it back.
+    AsymmetricTrust { trusted: String },
+    /// The validator's quorum set does not contain the validator itself.
+    MissingSelf,
+    /// The quorum set references a validator that is not in the input.
+    UnknownValidator(String),
+    /// The validator is in the trust core, but its quorum set differs from
+    /// the one most of the trust core uses.
+    DivergingTopTier,
+}
+
+/// A lint finding in the quorum set of `validator`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Lint {
+    pub severity: Severity,
+    pub validator: String,
+    pub kind: LintKind,
+}
+
+impl std::fmt::Display for Lint {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}: {}: ", self.severity, self.validator)?;
+        match &self.kind {
+            LintKind::SmallOrganization { organization, size } => {
+                write!(
+                    f,
+                    "organization {organization} is too small ({size} validators)"
+                )
+            }
+            LintKind::InnerThresholdOne { path, entries } => {
+                write!(f, "inner set at {path:?} has threshold 1 of {entries}")
+            }
+            LintKind::AsymmetricTrust { trusted } => {
+                write!(f, "trusts {trusted}, which does not trust it back")
+            }
+            LintKind::MissingSelf => write!(f, "quorum set does not contain itself"),
+            LintKind::UnknownValidator(v) => write!(f, "quorum set references unknown {v}"),
+            LintKind::DivergingTopTier => {
+                write!(f, "quorum set differs from the rest of the top tier")
+            }
+        }
+    }
+}
+
+fn collect<'a>(qset: &'a InternalScpQuorumSet, out: &mut BTreeSet<&'a str>) {
+    out.extend(qset.validators.iter().map(|v| v.as_str()));
+    qset.inner_sets.iter().for_each(|inner| collect(inner, out));
+}
+
+fn inner_threshold_one(
+    qset: &InternalScpQuorumSet,
+    path: &mut Vec<usize>,
+    out: &mut Vec<LintKind>,
+) {
+    let entries = qset.validators.len() + qset.inner_sets.len();
+    if !path.is_empty() && qset.threshold == 1 && entries > 1 {
+        out.push(LintKind::InnerThresholdOne {
+            path: path.clone(),
+            entries,
+        });
+    }
+    for (i, inner) in qset.inner_sets.iter().enumerate() {
+        path.push(i);
+        inner_threshold_one(inner, path, out);
+        path.pop();
+    }
+}
+
+// A canonical form of a quorum set, ignoring the order of its entries.
+#[derive(PartialEq, Eq, PartialOrd, Ord)]
+struct Canonical<'a> {
+    threshold: u32,
+    validators: BTreeSet<&'a str>,
+    inner_sets: BTreeSet<Canonical<'a>>,
+}
+
+impl<'a> Canonical<'a> {
+    fn new(qset: &'a InternalScpQuorumSet) -> Self {
+        Self {
+            threshold: qset.threshold,
+            validators: qset.validators.iter().map(|v| v.as_str()).collect(),
+            inner_sets: qset.inner_sets.iter().map(Canonical::new).collect(),
+        }
+    }
+}
+
+impl Fbas {
+    pub(crate) fn lint(&self, organizations: &Organizations) -> Vec<Lint> {
+        let filtered = self
+            .missing
+            .iter()
+            .filter(|node| node.reason == MissingReason::Filtered)
+            .map(|node| node.validator.as_str())
+            .collect::<BTreeSet<_>>();
+        let unknown = self
+            .missing
+            .iter()
+            .filter(|node| node.reason == MissingReason::UnknownValidator)
+            .map(|node| node.validator.as_str())
+            .collect::<BTreeSet<_>>();
+        let trusts = self
+            .quorum_set_map
+            .iter()
+            .filter(|(v, _)| !filtered.contains(v.as_str()))
+            .map(|(v, qset)| {
+                let mut trusted = BTreeSet::new();
+                collect(qset, &mut trusted);
+                (v.as_str(), trusted)
+            })
+            .collect::<BTreeMap<_, _>>();
+
+        let mut core = trusts.keys().copied().collect::<BTreeSet<_>>();
+        loop {
+            let trusted = core
+                .iter()
+                .flat_map(|v| trusts[v].iter().filter(move |t| *t != v))
+                .filter(|t| trusts.contains_key(*t))
+                .copied()
+                .collect::<BTreeSet<_>>();
+            let next = core
+                .intersection(&trusted)
+                .copied()
+                .collect::<BTreeSet<_>>();
+            if next.len() == core.len() {
+                break;
+            }
+            core = next;
+        }
+        let mut usage: BTreeMap<Canonical, usize> = BTreeMap::new();
+        for v in core.iter() {
+            *usage
+                .entry(Canonical::new(&self.quorum_set_map[*v]))
+                .or_default() += 1;
+        }
+        let common = usage.into_iter().max_by_key(|(_, count)| *count);
+
+        let mut lints = vec![];
+        let mut report = |severity, validator: &str, kind| {
+            lints.push(Lint {
+                severity,
+                validator: validator.to_string(),
+                kind,
+            })
+        };
+        for (organization, validators) in organizations.iter() {
+            if validators.len() >= MIN_ORGANIZATION_SIZE {
+                continue;
+            }
+            for v in validators
+                .iter()
+                .filter(|v| trusts.contains_key(v.as_str()))
+            {
+                report(
+                    Severity::Warning,
+                    v,
+                    LintKind::SmallOrganization {
+                        organization: organization.to_string(),
+                        size: validators.len(),
+                    },
+                );
+            }
+        }
+        for (v, trusted) in trusts.iter() {
+            let qset = &self.quorum_set_map[*v];
+            let mut inner = vec![];
+            inner_threshold_one(qset, &mut vec![], &mut inner);
+            for kind in inner.into_iter() {
+                report(Severity::Warning, v, kind);
+            }
+            if !trusted.contains(v) {
+                report(Severity::Warning, v, LintKind::MissingSelf);
+            }
+            for t in trusted.iter().filter(|t| unknown.contains(*t)) {
+                report(
+                    Severity::Warning,
+                    v,
+                    LintKind::UnknownValidator(t.to_string()),
+                );
+            }
+            if !core.contains(v) {
+                continue;
+            }
+            for t in trusted.iter().filter(|t| *t != v) {
+                if trusts.get(t).is_some_and(|back| !back.contains(v)) {
+                    report(
+                        Severity::Info,
+                        v,
+                        LintKind::AsymmetricTrust {
+                            trusted: t.to_string(),
+                        },
+                    );
+                }
+            }
+            if common
+                .as_ref()
+                .is_some_and(|(common, _)| *common != Canonical::new(qset))
+            {
+                report(Severity::Warning, v, LintKind::DivergingTopTier);
+            }
+        }
+        lints
+    }
+}
+
+impl FbasAnalyzer {
+    /// Checks the quorum sets for common configuration mistakes, with
+    /// organizations as grouped by `organizations`. The findings are
+    /// independent of whether the network enjoys quorum intersection.
+    pub fn lint(&self, organizations: &Organizations) -> Vec<Lint> {
+        self.fbas().lint(organizations)
+    }
+}
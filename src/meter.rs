@@ -0,0 +1,165 @@
+use crate::allocator::{get_memory_usage, is_installed, set_memory_limit};
+use log::warn;
+use std::{
+    cell::Cell,
+    fmt::Debug,
+    rc::Rc,
+    time::{Duration, Instant},
+};
+
+/// Source of time measurements for a `ResourceLimiter`.
+pub trait Clock: Debug {
+    /// Returns the time elapsed since an arbitrary, fixed origin.
+    fn now(&self) -> Duration;
+}
+
+/// Source of memory usage measurements for a `ResourceLimiter`.
+pub trait MemoryMeter: Debug {
+    /// Returns the current memory usage in bytes.
+    fn usage_bytes(&self) -> usize;
+
+    /// Called with the memory limit when the `ResourceLimiter` is created, for
+    /// meters that can also enforce the limit at allocation time.
+    fn set_limit(&self, _bytes: usize) {}
+}
+
+/// Wall-clock time, measured with `Instant`.
+#[derive(Debug, Clone, Copy)]
+pub struct SystemClock {
+    origin: Instant,
+}
+
+impl SystemClock {
+    pub fn new() -> Self {
+        Self {
+            origin: Instant::now(),
+        }
+    }
+}
+
+impl Default for SystemClock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Clock for SystemClock {
+    fn now(&self) -> Duration {
+        self.origin.elapsed()
+    }
+}
+
+/// Memory usage as tracked by the `LimitedAllocator`.
+///
+/// WARNING: setting the limit sets a global memory limit that affects the
+/// entire process. The memory limit is enforced by the global allocator and
+/// will cause allocation failures when exceeded. Use with caution in
+/// multi-threaded environments or when other parts of the process may
+/// allocate memory concurrently.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct AllocatorMeter;
+
+impl MemoryMeter for AllocatorMeter {
+    fn usage_bytes(&self) -> usize {
+        get_memory_usage()
+    }
+
+    fn set_limit(&self, bytes: usize) {
+        set_memory_limit(bytes);
+        if bytes != usize::MAX && !is_installed() {
+            warn!( target: "SCP",
+                "Memory limit of {} bytes will not be enforced: no `LimitedAllocator` is installed as the global allocator",
+                bytes
+            );
+        }
+    }
+}
+
+/// Resident set size of the process, read from `/proc/self/status`. This does
+/// not require the `LimitedAllocator`, but the limit is only checked when the
+/// `ResourceLimiter` measures, not at allocation time. Reports zero on
+/// platforms without procfs.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ProcRssMeter;
+
+impl MemoryMeter for ProcRssMeter {
+    fn usage_bytes(&self) -> usize {
+        let Ok(status) = std::fs::read_to_string("/proc/self/status") else {
+            return 0;
+        };
+        status
+            .lines()
+            .find_map(|line| line.strip_prefix("VmRSS:"))
+            .and_then(|rss| {
+                rss.trim()
+                    .trim_end_matches("kB")
+                    .trim()
+                    .parse::<usize>()
+                    .ok()
+            })
+            .map_or(0, |kb| kb.saturating_mul(1024))
+    }
+}
+
+/// A manually driven clock for deterministic tests. Clones share the same
+/// time. Optionally, every reading advances the time by a fixed step, which
+/// makes time limits trip after a deterministic amount of work.
+#[derive(Debug, Clone, Default)]
+pub struct MockClock {
+    time: Rc<Cell<Duration>>,
+    step: Duration,
+}
+
+impl MockClock {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_step(step: Duration) -> Self {
+        Self {
+            time: Rc::default(),
+            step,
+        }
+    }
+
+    pub fn set(&self, time: Duration) {
+        self.time.set(time);
+    }
+
+    pub fn advance(&self, duration: Duration) {
+        self.time.set(self.time.get().saturating_add(duration));
+    }
+}
+
+impl Clock for MockClock {
+    fn now(&self) -> Duration {
+        let now = self.time.get();
+        self.advance(self.step);
+        now
+    }
+}
+
+/// A manually driven memory meter for deterministic tests. Clones share the
+/// same usage.
+#[derive(Debug, Clone, Default)]
+pub struct MockMeter {
+    usage: Rc<Cell<usize>>,
+}
+
+impl MockMeter {
+    pub fn new(usage_bytes: usize) -> Self {
+        Self {
+            usage: Rc::new(Cell::new(usage_bytes)),
+        }
+    }
+
+    pub fn set(&self, usage_bytes: usize) {
+        self.usage.set(usage_bytes);
+    }
+}
+
+impl MemoryMeter for MockMeter {
+    fn usage_bytes(&self) -> usize {
+        self.usage.get()
+    }
+}
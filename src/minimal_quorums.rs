@@ -0,0 +1,104 @@
+use crate::{
+    fbas::FbasError,
+    fbas_analyze::{solve_resumable, FbasAnalyzer, LimitedSolver},
+    proof::{AnalyzerCallbacks, ClauseSink},
+    resource_limiter::Phase,
+};
+use batsat::{Lit, Solver};
+use petgraph::graph::NodeIndex;
+use std::collections::{BTreeMap, BTreeSet};
+
+// Enumerates minimal quorums with a solver for a single quorum (formulas 1 and
+// 3 of the analyzer). Every quorum the solver finds is shrunk to a minimal
+// quorum with the quorum fixpoint, and a clause then excludes all supersets of
+// that minimal quorum. Any quorum found afterwards contains a minimal quorum
+// not found yet, so the enumeration ends, with an `UNSAT` result, once all of
+// them are found. Restricted to a validator, the solver only looks for quorums
+// containing it, and minimal quorums without it are excluded but not listed.
+
+/// Minimal quorums found by `FbasAnalyzer::enumerate_minimal_quorums`.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct MinimalQuorums {
+    pub quorums: Vec<Vec<String>>,
+    /// Whether the enumeration stopped at the count limit, with more minimal
+    /// quorums left.
+    pub truncated: bool,
+}
+
+impl FbasAnalyzer {
+    /// Enumerates the minimal quorums, or only those containing `validator`,
+    /// up to `max_count` of them. The enumeration shares the analyzer's
+    /// `ResourceLimiter`, but not its solver.
+    pub fn enumerate_minimal_quorums(
+        &self,
+        validator: Option<&str>,
+        max_count: usize,
+    ) -> Result<MinimalQuorums, FbasError> {
+        let fbas = self.fbas();
+        let validator = validator.map(|v| fbas.validator_named(v)).transpose()?;
+        let limiter = self.resource_limiter();
+        limiter.enter_phase(Phase::Formula);
+        let mut solver = Solver::new(Default::default(), AnalyzerCallbacks::new(limiter.clone()));
+        let mut sink = LimitedSolver(&mut solver);
+        let vars = fbas
+            .graph
+            .node_indices()
+            .map(|ni| (ni, sink.new_var()))
+            .collect::<BTreeMap<_, _>>();
+        let in_quorum = |ni: &NodeIndex, is_member: bool| {
+            vars.get(ni)
+                .map(|var| Lit::new(*var, is_member))
+                .ok_or(FbasError::InternalError("Node index not found"))
+        };
+        let no_faults = BTreeMap::new();
+        Self::add_non_empty_clauses(fbas, &|ni| in_quorum(ni, true), &no_faults, &mut sink)?;
+        Self::add_quorum_relation_clauses(fbas, &in_quorum, &no_faults, &mut sink)?;
+        let assumptions = validator
+            .map(|ni| in_quorum(&ni, true))
+            .transpose()?
+            .into_iter()
+            .collect::<Vec<_>>();
+        limiter.enter_phase(Phase::Solve);
+
+        let mut result = MinimalQuorums::default();
+        while let Some(quorum) = solve_resumable(&mut solver, &assumptions, |value| {
+            fbas.validators
+                .iter()
+                .filter(|ni| in_quorum(ni, true).is_ok_and(value))
+                .copied()
+                .collect::<BTreeSet<_>>()
+        })? {
+            let minimal = match validator {
+                Some(ni) => fbas.minimal_quorum_within(
+                    &fbas.minimal_quorum_containing(&quorum, ni, &limiter)?,
+                    &limiter,
+                )?,
+                None => fbas.minimal_quorum_within(&quorum, &limiter)?,
+            };
+            if minimal.is_empty() {
+                return Err(FbasError::InternalError(
+                    "solver found a quorum the fixpoint does not confirm",
+                ));
+            }
+            let mut clause = minimal
+                .iter()
+                .map(|ni| in_quorum(ni, false))
+                .collect::<Result<Vec<_>, _>>()?;
+            LimitedSolver(&mut solver).add_clause(&mut clause)?;
+            if validator.is_some_and(|ni| !minimal.contains(&ni)) {
+                continue;
+            }
+            if result.quorums.len() == max_count {
+                result.truncated = true;
+                break;
+            }
+            result.quorums.push(
+                minimal
+                    .iter()
+                    .map(|ni| fbas.try_get_validator_string(ni))
+                    .collect::<Result<_, _>>()?,
+            );
+        }
+        Ok(result)
+    }
+}
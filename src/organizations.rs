@@ -0,0 +1,228 @@
+use crate::{
+    fbas::{Fbas, FbasError, InternalScpQuorumSet, MissingReason, ParseOptions, QuorumSetMap},
+    ResourceLimiter,
+};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    rc::Rc,
+};
+
+/// How validators are grouped into organizations.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum OrganizationGrouping {
+    /// By the home domain in the node metadata.
+    HomeDomain,
+    /// By the stellarbeat organization id in the node metadata.
+    OrganizationId,
+    /// By a mapping from validator to organization name.
+    Mapping(BTreeMap<String, String>),
+}
+
+/// The organizations of an FBAS and their validators. A validator that the
+/// grouping does not assign to any organization forms an organization of its
+/// own, named after the validator.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Organizations {
+    members: BTreeMap<String, Vec<String>>,
+    organization_of: BTreeMap<String, String>,
+}
+
+impl Organizations {
+    pub(crate) fn new(fbas: &Fbas, grouping: &OrganizationGrouping) -> Self {
+        fn collect(qset: &InternalScpQuorumSet, out: &mut BTreeSet<String>) {
+            out.extend(qset.validators.iter().cloned());
+            qset.inner_sets.iter().for_each(|inner| collect(inner, out));
+        }
+        // every validator, including the ones only referenced in quorum sets
+        let mut validators = BTreeSet::new();
+        for (node, qset) in fbas.quorum_set_map.iter() {
+            validators.insert(node.clone());
+            collect(qset, &mut validators);
+        }
+        validators.extend(fbas.missing.iter().map(|m| m.validator.clone()));
+
+        let mut organizations = Organizations::default();
+        for validator in validators {
+            let metadata = fbas.metadata.get(&validator);
+            let organization = match grouping {
+                OrganizationGrouping::HomeDomain => metadata.and_then(|m| m.home_domain.clone()),
+                OrganizationGrouping::OrganizationId => {
+                    metadata.and_then(|m| m.organization_id.clone())
+                }
+                OrganizationGrouping::Mapping(mapping) => mapping.get(&validator).cloned(),
+            }
+            .unwrap_or_else(|| validator.clone());
+            organizations
+                .members
+                .entry(organization.clone())
+                .or_default()
+                .push(validator.clone());
+            organizations
+                .organization_of
+                .insert(validator, organization);
+        }
+        organizations
+    }
+
+    /// Returns the organization `validator` belongs to.
+    pub fn organization_of(&self, validator: &str) -> Option<&str> {
+        self.organization_of.get(validator).map(String::as_str)
+    }
+
+    /// Returns the validators of `organization`.
+    pub fn validators_of(&self, organization: &str) -> Option<&[String]> {
+        self.members.get(organization).map(Vec::as_slice)
+    }
+
+    /// Iterates over the organizations and their validators.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &[String])> {
+        self.members
+            .iter()
+            .map(|(org, validators)| (org.as_str(), validators.as_slice()))
+    }
+
+    pub fn len(&self) -> usize {
+        self.members.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.members.is_empty()
+    }
+
+    /// Returns the organizations of `validators`, e.g. to express a potential
+    /// split per organization.
+    pub fn group<S: AsRef<str>>(&self, validators: &[S]) -> Vec<String> {
+        validators
+            .iter()
+            .filter_map(|v| self.organization_of(v.as_ref()))
+            .map(str::to_string)
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .collect()
+    }
+
+    // Replaces the validators of a quorum set by their organizations. The
+    // entries referring to the same organization merge into one entry weighing
+    // as much as they did together, and an inner set satisfied exactly when a
+    // single organization is present becomes that organization. The threshold
+    // becomes the fewest entries that reach the original threshold whichever
+    // they are, so the collapsed quorum set is never easier to satisfy than
+    // the original one with all validators of its organizations. It is just
+    // as easy, i.e. exact, unless fewer but heavier entries reach the original
+    // threshold as well, which the returned flag reports.
+    fn collapse(&self, qset: &InternalScpQuorumSet) -> (InternalScpQuorumSet, bool) {
+        fn add(orgs: &mut Vec<(String, u32)>, org: &str) {
+            match orgs.iter_mut().find(|(o, _)| o == org) {
+                Some((_, weight)) => *weight += 1,
+                None => orgs.push((org.to_string(), 1)),
+            }
+        }
+        let mut orgs = vec![];
+        let mut inner_sets = vec![];
+        let mut exact = true;
+        for v in qset.validators.iter() {
+            add(&mut orgs, self.organization_of(v).unwrap_or(v));
+        }
+        for inner in qset.inner_sets.iter() {
+            let (inner, inner_exact) = self.collapse(inner);
+            exact &= inner_exact;
+            match inner.validators.as_slice() {
+                [org] if inner.inner_sets.is_empty() && inner.threshold == 1 => add(&mut orgs, org),
+                _ => inner_sets.push(inner),
+            }
+        }
+
+        let mut weights: Vec<u32> = orgs.iter().map(|(_, weight)| *weight).collect();
+        weights.extend(inner_sets.iter().map(|_| 1));
+        weights.sort_unstable();
+        let mut threshold = qset.threshold;
+        // vacuous and unsatisfiable quorum sets stay as they are
+        if threshold > 0 && weights.iter().sum::<u32>() >= threshold {
+            let mut reached = 0;
+            let count = weights
+                .iter()
+                .take_while(|weight| {
+                    let missing = reached < threshold;
+                    reached += *weight;
+                    missing
+                })
+                .count();
+            exact &= weights.iter().rev().take(count - 1).sum::<u32>() < threshold;
+            threshold = count as u32;
+        }
+        let qset = InternalScpQuorumSet {
+            threshold,
+            validators: orgs.into_iter().map(|(org, _)| org).collect(),
+            inner_sets,
+        };
+        (qset, exact)
+    }
+}
+
+impl Fbas {
+    /// Builds the FBAS of the logical organization nodes. An organization's
+    /// quorum set requires the collapsed quorum sets of all of its
+    /// validators.
+    pub(crate) fn collapse_organizations(
+        &self,
+        organizations: &Organizations,
+        resource_limiter: &ResourceLimiter,
+    ) -> Result<Self, FbasError> {
+        let mut quorum_set_map = self.quorum_set_map.clone();
+        Self::filter_nodes(&mut quorum_set_map, &self.metadata, &self.options);
+
+        let mut candidates: BTreeMap<&str, BTreeSet<InternalScpQuorumSet>> = BTreeMap::new();
+        let mut approximate = BTreeSet::new();
+        for (validator, qset) in quorum_set_map.iter() {
+            let org = organizations
+                .organization_of(validator)
+                .unwrap_or(validator);
+            let (qset, exact) = organizations.collapse(qset);
+            if !exact {
+                approximate.insert(org.to_string());
+            }
+            candidates.entry(org).or_default().insert(qset);
+        }
+
+        let mut org_map = QuorumSetMap::new();
+        for (org, mut qsets) in candidates.into_iter() {
+            let qset = match qsets.len() {
+                1 => qsets.pop_first(),
+                // validators disagreeing on their quorum sets need all of them
+                len => Some(InternalScpQuorumSet {
+                    threshold: len as u32,
+                    validators: vec![],
+                    inner_sets: qsets.into_iter().collect(),
+                }),
+            }
+            .ok_or(FbasError::InternalError("organization without quorum set"))?;
+            org_map.insert(org.to_string(), Rc::new(qset));
+        }
+
+        let missing_qsets = self
+            .missing
+            .iter()
+            .filter(|m| m.reason == MissingReason::MissingQuorumSet)
+            .filter_map(|m| organizations.organization_of(&m.validator))
+            .filter(|org| !org_map.contains_key(*org))
+            .map(str::to_string)
+            .collect();
+        // combining diverging quorum sets adds a level, and the collapsed
+        // quorum sets are not input to hold to stellar-core's rules
+        let options = ParseOptions {
+            max_depth: self.options.max_depth + 1,
+            extra_checks: false,
+            strict: false,
+            ..self.options.clone()
+        };
+        let mut fbas = Self::from_quorum_set_map(
+            org_map,
+            &missing_qsets,
+            Default::default(),
+            &options,
+            resource_limiter,
+        )?;
+        fbas.approximate = approximate.into_iter().collect();
+        Ok(fbas)
+    }
+}
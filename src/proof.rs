@@ -0,0 +1,154 @@
+use crate::{fbas::FbasError, resource_limiter::ResourceLimiter};
+use batsat::{
+    callbacks::{Callbacks, ProgressStatus},
+    drat::Proof,
+    lbool, ClauseKind, Lit, Var,
+};
+use std::fmt::Write;
+
+// An UNSAT verdict can be independently audited with a DRAT proof checker
+// (e.g. `drat-trim formula.cnf proof.drat`). This requires two artifacts:
+//
+// 1. The formula in DIMACS CNF, with the exact variable numbering used by the
+//    solver. The formula is not kept around after it has been handed to the
+//    solver. Instead it is regenerated on demand by replaying
+//    `construct_formula` into a `DimacsFormula`, which is deterministic.
+//
+// 2. The DRAT proof, i.e. the sequence of clauses learnt (and deleted) by the
+//    solver during search. Every clause learnt by batsat's conflict analysis
+//    is a RUP (reverse unit propagation) clause, so the proof is also a valid
+//    DRUP proof. It is recorded through the solver callbacks, which is why
+//    proof logging must be enabled before calling `solve`.
+
+/// Converts a solver literal into its DIMACS representation (1-based variable
+/// index, negative for negated literals).
+fn dimacs_lit(lit: Lit) -> i64 {
+    let var = lit.var().idx() as i64 + 1;
+    if lit.sign() {
+        var
+    } else {
+        -var
+    }
+}
+
+/// Destination of the clauses produced by `construct_formula`.
+pub(crate) trait ClauseSink {
+    fn new_var(&mut self) -> Var;
+    fn add_clause(&mut self, clause: &mut Vec<Lit>) -> Result<(), FbasError>;
+}
+
+/// Collects a formula in memory so that it can be written out as DIMACS CNF.
+#[derive(Debug, Default)]
+pub(crate) struct DimacsFormula {
+    num_vars: u32,
+    clauses: Vec<Vec<Lit>>,
+}
+
+impl DimacsFormula {
+    /// Creates an empty formula whose first `num_vars` variables have already
+    /// been allocated (i.e. the quorum membership variables).
+    pub fn with_vars(num_vars: u32) -> Self {
+        Self {
+            num_vars,
+            clauses: vec![],
+        }
+    }
+
+    pub fn num_vars(&self) -> u32 {
+        self.num_vars
+    }
+
+    pub fn num_clauses(&self) -> usize {
+        self.clauses.len()
+    }
+
+    pub fn to_dimacs(&self) -> String {
+        let mut out = String::new();
+        let _ = writeln!(out, "p cnf {} {}", self.num_vars, self.clauses.len());
+        for clause in self.clauses.iter() {
+            for lit in clause.iter() {
+                let _ = write!(out, "{} ", dimacs_lit(*lit));
+            }
+            out.push_str("0\n");
+        }
+        out
+    }
+}
+
+impl ClauseSink for DimacsFormula {
+    fn new_var(&mut self) -> Var {
+        let var = Var::unsafe_from_idx(self.num_vars);
+        self.num_vars += 1;
+        var
+    }
+
+    fn add_clause(&mut self, clause: &mut Vec<Lit>) -> Result<(), FbasError> {
+        self.clauses.push(clause.clone());
+        Ok(())
+    }
+}
+
+/// The solver callbacks used by `FbasAnalyzer`. Resource limiting is delegated
+/// to the `ResourceLimiter`; additionally, clauses learnt and deleted by the
+/// solver are recorded as a DRAT proof if proof logging has been enabled.
+pub(crate) struct AnalyzerCallbacks {
+    pub limiter: ResourceLimiter,
+    proof: Option<Proof>,
+}
+
+impl AnalyzerCallbacks {
+    pub fn new(limiter: ResourceLimiter) -> Self {
+        Self {
+            limiter,
+            proof: None,
+        }
+    }
+
+    pub fn enable_proof(&mut self) {
+        if self.proof.is_none() {
+            self.proof = Some(Proof::new());
+        }
+    }
+
+    pub fn proof(&self) -> Option<&Proof> {
+        self.proof.as_ref()
+    }
+}
+
+impl Callbacks for AnalyzerCallbacks {
+    fn on_start(&mut self) {
+        self.limiter.on_start()
+    }
+
+    fn on_result(&mut self, s: lbool) {
+        self.limiter.on_result(s)
+    }
+
+    fn on_progress<F>(&mut self, p: F)
+    where
+        F: FnOnce() -> ProgressStatus,
+    {
+        self.limiter.on_progress(p)
+    }
+
+    fn on_gc(&mut self, old: usize, new: usize) {
+        self.limiter.on_gc(old, new)
+    }
+
+    fn on_new_clause(&mut self, c: &[Lit], src: ClauseKind) {
+        self.limiter.on_new_clause(c, src);
+        if let Some(proof) = self.proof.as_mut() {
+            proof.create_clause(&c);
+        }
+    }
+
+    fn on_delete_clause(&mut self, c: &[Lit]) {
+        if let Some(proof) = self.proof.as_mut() {
+            proof.delete_clause(&c);
+        }
+    }
+
+    fn stop(&self) -> bool {
+        self.limiter.stop()
+    }
+}
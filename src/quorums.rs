@@ -0,0 +1,302 @@
+use crate::{
+    fbas::{Fbas, FbasError, InternalScpQuorumSet, MissingReason, Vertex},
+    fbas_analyze::FbasAnalyzer,
+    resource_limiter::ResourceLimiter,
+};
+use petgraph::graph::NodeIndex;
+use std::collections::{BTreeMap, BTreeSet};
+
+// Quorum computations that evaluate quorum sets directly over the graph,
+// without the SAT solver. A set of validators is a quorum if every member has
+// its quorum set satisfied by the set. The greatest quorum within a set of
+// validators is found as a fixpoint: repeatedly drop the members whose quorum
+// set is not satisfied by the remaining ones. Any quorum within the set
+// survives this, so the result is the union of all quorums within it.
+
+impl Fbas {
+    // Whether the vertex `ni` is satisfied by `members`: a validator by being
+    // a member, a quorum set by reaching its threshold.
+    fn is_satisfied(
+        &self,
+        ni: NodeIndex,
+        members: &BTreeSet<NodeIndex>,
+        memo: &mut BTreeMap<NodeIndex, bool>,
+    ) -> bool {
+        if let Some(satisfied) = memo.get(&ni) {
+            return *satisfied;
+        }
+        let satisfied = match self.graph.node_weight(ni) {
+            Some(Vertex::Validator(_)) => members.contains(&ni),
+            Some(Vertex::QSet(qset)) => {
+                let mut count = 0;
+                for successor in self.graph.neighbors(ni) {
+                    if count >= qset.threshold {
+                        break;
+                    }
+                    if self.is_satisfied(successor, members, memo) {
+                        count += 1;
+                    }
+                }
+                count >= qset.threshold
+            }
+            None => false,
+        };
+        memo.insert(ni, satisfied);
+        satisfied
+    }
+
+    // Whether the quorum set of validator `ni` is satisfied by `members`.
+    fn has_slice_in(&self, ni: NodeIndex, members: &BTreeSet<NodeIndex>) -> bool {
+        self.graph
+            .neighbors(ni)
+            .next()
+            .is_some_and(|qset| self.is_satisfied(qset, members, &mut BTreeMap::new()))
+    }
+
+    /// Returns the greatest quorum within `available`, which is empty if there
+    /// is none. Like formula 1 of the analyzer, a quorum must contain a
+    /// validator whose quorum set was not assumed by a `MissingNodePolicy`.
+    pub(crate) fn greatest_quorum_within(
+        &self,
+        available: &BTreeSet<NodeIndex>,
+    ) -> BTreeSet<NodeIndex> {
+        let mut members = available
+            .iter()
+            .filter(|ni| matches!(self.graph.node_weight(**ni), Some(Vertex::Validator(_))))
+            .copied()
+            .collect::<BTreeSet<_>>();
+        loop {
+            let mut memo = BTreeMap::new();
+            let satisfied = members
+                .iter()
+                .filter(|v| {
+                    self.graph
+                        .neighbors(**v)
+                        .next()
+                        .is_some_and(|qset| self.is_satisfied(qset, &members, &mut memo))
+                })
+                .copied()
+                .collect::<BTreeSet<_>>();
+            if satisfied.len() == members.len() {
+                break;
+            }
+            members = satisfied;
+        }
+        if members.iter().all(|v| self.assumed.contains_key(v)) {
+            members.clear();
+        }
+        members
+    }
+
+    /// Shrinks `quorum` to a minimal quorum contained in it, by dropping one
+    /// validator at a time as long as a quorum remains. This takes a fixpoint
+    /// per validator, so the limits are enforced before each of them.
+    pub(crate) fn minimal_quorum_within(
+        &self,
+        quorum: &BTreeSet<NodeIndex>,
+        resource_limiter: &ResourceLimiter,
+    ) -> Result<BTreeSet<NodeIndex>, FbasError> {
+        let mut quorum = self.greatest_quorum_within(quorum);
+        for v in quorum.clone() {
+            if !quorum.contains(&v) {
+                continue;
+            }
+            resource_limiter.measure_and_enforce_limits()?;
+            let mut candidate = quorum.clone();
+            candidate.remove(&v);
+            let smaller = self.greatest_quorum_within(&candidate);
+            if !smaller.is_empty() {
+                quorum = smaller;
+            }
+        }
+        Ok(quorum)
+    }
+
+    /// Shrinks `quorum` to a quorum that still contains `validator` but no
+    /// smaller quorum that does. It may still contain a smaller quorum
+    /// without `validator`. Like `minimal_quorum_within`, this enforces the
+    /// limits before each fixpoint.
+    pub(crate) fn minimal_quorum_containing(
+        &self,
+        quorum: &BTreeSet<NodeIndex>,
+        validator: NodeIndex,
+        resource_limiter: &ResourceLimiter,
+    ) -> Result<BTreeSet<NodeIndex>, FbasError> {
+        let mut quorum = self.greatest_quorum_within(quorum);
+        for v in quorum.clone() {
+            if v == validator || !quorum.contains(&v) {
+                continue;
+            }
+            resource_limiter.measure_and_enforce_limits()?;
+            let mut candidate = quorum.clone();
+            candidate.remove(&v);
+            let smaller = self.greatest_quorum_within(&candidate);
+            if smaller.contains(&validator) {
+                quorum = smaller;
+            }
+        }
+        Ok(quorum)
+    }
+
+    pub(crate) fn validator_named(&self, validator: &str) -> Result<NodeIndex, FbasError> {
+        self.validators
+            .iter()
+            .find(|ni| matches!(self.graph.node_weight(**ni), Some(Vertex::Validator(v)) if v == validator))
+            .copied()
+            .ok_or_else(|| FbasError::UnknownValidator(validator.to_string()))
+    }
+
+    /// Restricts the FBAS to the transitive closure of `validator`'s quorum
+    /// set: the validators it trusts, the ones they trust, and so on. This is
+    /// the part of the network `validator` itself depends on, and the part
+    /// stellar-core analyzes on its node. The closure follows the quorum sets
+    /// as parsed, so the validators without a known quorum set it references
+    /// keep their `MissingReason` and `MissingNodePolicy`, whether or not
+    /// they are in the graph.
+    pub(crate) fn restrict_to_closure(
+        &self,
+        validator: &str,
+        resource_limiter: &ResourceLimiter,
+    ) -> Result<Self, FbasError> {
+        fn collect<'a>(qset: &'a InternalScpQuorumSet, out: &mut Vec<&'a str>) {
+            out.extend(qset.validators.iter().map(|v| v.as_str()));
+            qset.inner_sets.iter().for_each(|inner| collect(inner, out));
+        }
+        self.validator_named(validator)?;
+        // filtered validators are in the parsed map, but trust no one
+        let missing = self
+            .missing
+            .iter()
+            .map(|m| m.validator.as_str())
+            .collect::<BTreeSet<_>>();
+        let mut closure = BTreeSet::from([validator]);
+        let mut stack = vec![validator];
+        while let Some(v) = stack.pop() {
+            resource_limiter.measure_and_enforce_limits()?;
+            let Some(qset) = self.quorum_set_map.get(v).filter(|_| !missing.contains(v)) else {
+                continue;
+            };
+            let mut trusted = vec![];
+            collect(qset, &mut trusted);
+            stack.extend(trusted.into_iter().filter(|t| closure.insert(t)));
+        }
+
+        let quorum_set_map = self
+            .quorum_set_map
+            .iter()
+            .filter(|(v, _)| closure.contains(v.as_str()))
+            .map(|(v, qset)| (v.clone(), qset.clone()))
+            .collect();
+        let missing_qsets = self
+            .missing
+            .iter()
+            .filter(|m| m.reason == MissingReason::MissingQuorumSet)
+            .filter(|m| closure.contains(m.validator.as_str()))
+            .map(|m| m.validator.clone())
+            .collect();
+        let metadata = self
+            .metadata
+            .iter()
+            .filter(|(v, _)| closure.contains(v.as_str()))
+            .map(|(v, meta)| (v.clone(), meta.clone()))
+            .collect();
+        let mut fbas = Self::from_quorum_set_map(
+            quorum_set_map,
+            &missing_qsets,
+            metadata,
+            &self.options,
+            resource_limiter,
+        )?;
+        fbas.outside = self
+            .validators
+            .iter()
+            .map(|ni| self.try_get_validator_string(ni))
+            .filter(|v| v.as_ref().map_or(true, |v| !closure.contains(v.as_str())))
+            .collect::<Result<_, _>>()?;
+        Ok(fbas)
+    }
+
+    // The graph indices of the named validators, skipping unknown names.
+    fn validators_named<S: AsRef<str>>(&self, validators: &[S]) -> BTreeSet<NodeIndex> {
+        let names = validators
+            .iter()
+            .map(|v| v.as_ref())
+            .collect::<BTreeSet<_>>();
+        self.validators
+            .iter()
+            .filter(|ni| {
+                matches!(self.graph.node_weight(**ni),
+                    Some(Vertex::Validator(v)) if names.contains(v.as_str()))
+            })
+            .copied()
+            .collect()
+    }
+}
+
+impl FbasAnalyzer {
+    /// Returns whether a quorum can form if only `validators` are up, without
+    /// running the solver. Validators not in the network are ignored.
+    pub fn quorum_exists_within<S: AsRef<str>>(&self, validators: &[S]) -> bool {
+        let fbas = self.fbas();
+        !fbas
+            .greatest_quorum_within(&fbas.validators_named(validators))
+            .is_empty()
+    }
+
+    /// Returns the greatest quorum that can form if only `validators` are
+    /// up, i.e. the union of all such quorums, which is empty if there is
+    /// none. Validators not in the network are ignored.
+    pub fn greatest_quorum_within<S: AsRef<str>>(
+        &self,
+        validators: &[S],
+    ) -> Result<Vec<String>, FbasError> {
+        let fbas = self.fbas();
+        fbas.greatest_quorum_within(&fbas.validators_named(validators))
+            .iter()
+            .map(|ni| fbas.try_get_validator_string(ni))
+            .collect()
+    }
+
+    /// Returns whether `validators` form a quorum: every one of them has a
+    /// slice among them. As for the analysis, a set made up only of
+    /// validators with a quorum set assumed by a `MissingNodePolicy` is not a
+    /// quorum. Validators not in the network are ignored.
+    pub fn is_quorum<S: AsRef<str>>(&self, validators: &[S]) -> bool {
+        let fbas = self.fbas();
+        let members = fbas.validators_named(validators);
+        !members.is_empty()
+            && !members.iter().all(|v| fbas.assumed.contains_key(v))
+            && members.iter().all(|v| fbas.has_slice_in(*v, &members))
+    }
+
+    /// Returns whether `validators` contain a slice of `node`, i.e. satisfy
+    /// its quorum set.
+    pub fn is_quorum_slice<S: AsRef<str>>(
+        &self,
+        node: &str,
+        validators: &[S],
+    ) -> Result<bool, FbasError> {
+        let fbas = self.fbas();
+        let ni = fbas.validator_named(node)?;
+        Ok(fbas.has_slice_in(ni, &fbas.validators_named(validators)))
+    }
+
+    /// Returns whether `validators` are v-blocking for `node`, i.e. intersect
+    /// every slice of it, so that `node` has no slice without them.
+    pub fn is_v_blocking<S: AsRef<str>>(
+        &self,
+        node: &str,
+        validators: &[S],
+    ) -> Result<bool, FbasError> {
+        let fbas = self.fbas();
+        let ni = fbas.validator_named(node)?;
+        let blocking = fbas.validators_named(validators);
+        let others = fbas
+            .validators
+            .iter()
+            .filter(|v| !blocking.contains(v))
+            .copied()
+            .collect();
+        Ok(!fbas.has_slice_in(ni, &others))
+    }
+}
@@ -1,36 +1,38 @@
 use crate::{
-    allocator::{get_memory_usage, set_memory_limit},
+    meter::{AllocatorMeter, Clock, MemoryMeter, SystemClock},
     FbasError,
 };
 use batsat::{
-    callbacks::{Callbacks, ProgressStatus},
-    lbool,
+    callbacks::{AsyncInterrupt, Callbacks, ProgressStatus},
+    lbool, ClauseKind, Lit,
 };
 use log::{error, trace};
-use std::{
-    cell::RefCell,
-    rc::Rc,
-    time::{Duration, Instant},
-};
+use std::{cell::RefCell, rc::Rc, time::Duration};
 
-#[derive(Clone, Debug, Copy)]
+// When a propagation budget is set, the solver is paused every this many
+// conflicts so that its propagation count can be checked (it is not visible to
+// the callbacks).
+const PROPAGATION_CHECK_INTERVAL: u64 = 100;
+
+#[derive(Clone, Debug, Copy, Default)]
 pub struct ResourceQuantity {
     pub time: Duration,
     pub mem_bytes: usize,
+    pub conflicts: u64,
+    pub propagations: u64,
+    pub clauses: u64,
 }
 
 impl ResourceQuantity {
     pub fn zero() -> Self {
-        ResourceQuantity {
-            time: Duration::ZERO,
-            mem_bytes: 0,
-        }
+        ResourceQuantity::default()
     }
 
     pub fn new(time_ms: u64, mem_bytes: usize) -> Self {
         ResourceQuantity {
             time: Duration::from_millis(time_ms),
             mem_bytes,
+            ..Default::default()
         }
     }
 
@@ -39,74 +41,310 @@ impl ResourceQuantity {
     }
 }
 
+/// Deterministic budgets on the amount of work, which unlike time limits do
+/// not depend on machine load. `None` means unlimited. The budgets apply per
+/// phase: the work counters restart whenever a query enters a phase, i.e.
+/// once for constructing its formula and once for all of its solver runs, so
+/// every query on the same limiter gets the full budgets again.
+#[derive(Clone, Debug, Copy, Default, PartialEq, Eq)]
+pub struct WorkLimits {
+    /// Maximum number of conflicts encountered by the solver.
+    pub max_conflicts: Option<u64>,
+    /// Maximum number of unit propagations performed by the solver. This is
+    /// checked every `PROPAGATION_CHECK_INTERVAL` conflicts, so the budget may
+    /// be overrun by the propagations in between.
+    pub max_propagations: Option<u64>,
+    /// Maximum number of clauses generated while constructing the formula.
+    pub max_clauses: Option<u64>,
+}
+
+/// The resource whose limit has been exceeded.
+#[derive(Clone, Debug, Copy, PartialEq, Eq)]
+pub enum ResourceKind {
+    Time,
+    Memory,
+    Conflicts,
+    Propagations,
+    Clauses,
+}
+
+impl std::fmt::Display for ResourceKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ResourceKind::Time => write!(f, "time"),
+            ResourceKind::Memory => write!(f, "memory"),
+            ResourceKind::Conflicts => write!(f, "conflicts"),
+            ResourceKind::Propagations => write!(f, "propagations"),
+            ResourceKind::Clauses => write!(f, "clauses"),
+        }
+    }
+}
+
+/// The phases of an analysis. Each phase can be given its own budget with
+/// `ResourceLimiter::with_phase_limit`.
+#[derive(Clone, Debug, Copy, PartialEq, Eq)]
+pub enum Phase {
+    /// Decoding the input and building the quorum set graph.
+    Parse,
+    /// Constructing the SAT formula.
+    Formula,
+    /// Running the SAT solver.
+    Solve,
+}
+
+impl Phase {
+    fn index(self) -> usize {
+        match self {
+            Phase::Parse => 0,
+            Phase::Formula => 1,
+            Phase::Solve => 2,
+        }
+    }
+}
+
+impl std::fmt::Display for Phase {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Phase::Parse => write!(f, "parse"),
+            Phase::Formula => write!(f, "formula"),
+            Phase::Solve => write!(f, "solve"),
+        }
+    }
+}
+
+/// A snapshot of the solver's progress, passed to the progress callback of a
+/// `ResourceLimiter`.
+#[derive(Clone, Debug, Copy)]
+pub struct SolveProgress {
+    pub conflicts: u64,
+    pub clauses: u64,
+    pub learnt_clauses: u64,
+    pub elapsed: Duration,
+    pub mem_bytes: usize,
+}
+
+type ProgressCallback = Box<dyn FnMut(&SolveProgress)>;
+
+// Caller-provided hooks for cancelling the analysis and observing its progress.
+#[derive(Default)]
+struct Hooks {
+    interrupt: Option<AsyncInterrupt>,
+    on_progress: Option<ProgressCallback>,
+}
+
+impl std::fmt::Debug for Hooks {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Hooks")
+            .field("interrupt", &self.interrupt.is_some())
+            .field("on_progress", &self.on_progress.is_some())
+            .finish()
+    }
+}
+
 /// An implementation of the `Callbacks` trait that tracks and limits the memory usage and processing time of the solver.
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug)]
 pub(crate) struct ResourceLimiterImpl {
-    start_time: Instant,
+    clock: Rc<dyn Clock>,
+    meter: Rc<dyn MemoryMeter>,
+    start_time: Duration,
     start_memory: usize,
     limits: ResourceQuantity,
+    work_limits: WorkLimits,
     current_usage: ResourceQuantity,
+    peak_mem_bytes: usize,
+    phase: Phase,
+    // time elapsed when the current phase was entered
+    phase_start_time: Duration,
+    // time and memory limits of each phase, indexed by `Phase::index`
+    phase_limits: [Option<ResourceQuantity>; 3],
+    // conflict count at which the solver is paused to check propagations
+    next_checkpoint: u64,
+    hooks: Hooks,
 }
 
 #[derive(Debug, Clone)]
 pub struct ResourceLimiter(Rc<RefCell<ResourceLimiterImpl>>);
 
 impl ResourceLimiterImpl {
-    // WARNING: This function sets a global memory limit that affects the entire
-    // process. The memory limit is enforced by the global allocator and will
-    // cause allocation failures when exceeded. Use with caution in
-    // multi-threaded environments or when other parts of the process may
-    // allocate memory concurrently.
-    pub fn new(time_limit_ms: u64, global_mem_limit_bytes: usize) -> Self {
-        set_memory_limit(global_mem_limit_bytes);
+    pub fn new(
+        time_limit_ms: u64,
+        mem_limit_bytes: usize,
+        clock: Rc<dyn Clock>,
+        meter: Rc<dyn MemoryMeter>,
+    ) -> Self {
+        meter.set_limit(mem_limit_bytes);
         Self {
-            start_time: Instant::now(),
-            start_memory: get_memory_usage(),
-            limits: ResourceQuantity::new(time_limit_ms, global_mem_limit_bytes),
+            start_time: clock.now(),
+            start_memory: meter.usage_bytes(),
+            clock,
+            meter,
+            limits: ResourceQuantity::new(time_limit_ms, mem_limit_bytes),
+            work_limits: WorkLimits::default(),
             current_usage: ResourceQuantity::zero(),
+            peak_mem_bytes: 0,
+            phase: Phase::Parse,
+            phase_start_time: Duration::ZERO,
+            phase_limits: [None; 3],
+            next_checkpoint: PROPAGATION_CHECK_INTERVAL,
+            hooks: Hooks::default(),
         }
     }
 
     fn measure(&mut self, verbose: bool) {
-        let time = self.start_time.elapsed();
-        let mem_bytes = get_memory_usage().saturating_sub(self.start_memory);
+        let time = self.clock.now().saturating_sub(self.start_time);
+        let mem_bytes = self.meter.usage_bytes().saturating_sub(self.start_memory);
         if verbose {
             trace!( target: "SCP",
                 "Time elapsed: {} ms, Time limit: {} ms; Memory usage: {} bytes, Memory limit: {} bytes",
                 self.current_usage.time.as_millis(), self.limits.time.as_millis(), self.current_usage.mem_bytes, self.limits.mem_bytes
             );
         }
-        self.current_usage = ResourceQuantity { time, mem_bytes };
+        self.current_usage.time = time;
+        self.current_usage.mem_bytes = mem_bytes;
+        self.peak_mem_bytes = self.peak_mem_bytes.max(mem_bytes);
+    }
+
+    fn exceeded_resource(&self) -> Option<ResourceKind> {
+        let usage = &self.current_usage;
+        let over = |used: u64, limit: Option<u64>| limit.is_some_and(|limit| used > limit);
+        let phase_limits = self.phase_limits[self.phase.index()].unwrap_or(self.limits);
+        let phase_time = usage.time.saturating_sub(self.phase_start_time);
+        if usage.time > self.limits.time || phase_time > phase_limits.time {
+            Some(ResourceKind::Time)
+        } else if usage.mem_bytes > self.limits.mem_bytes.min(phase_limits.mem_bytes) {
+            Some(ResourceKind::Memory)
+        } else if over(usage.conflicts, self.work_limits.max_conflicts) {
+            Some(ResourceKind::Conflicts)
+        } else if over(usage.propagations, self.work_limits.max_propagations) {
+            Some(ResourceKind::Propagations)
+        } else if over(usage.clauses, self.work_limits.max_clauses) {
+            Some(ResourceKind::Clauses)
+        } else {
+            None
+        }
     }
 
     fn measure_and_enforce_limits(&mut self) -> Result<(), FbasError> {
+        if self
+            .hooks
+            .interrupt
+            .as_ref()
+            .is_some_and(|interrupt| interrupt.stop())
+        {
+            error!(target: "SCP", "Analysis interrupted");
+            return Err(FbasError::Interrupted);
+        }
         self.measure(false);
-        if self.current_usage.exceeds(&self.limits) {
+        if let Some(resource) = self.exceeded_resource() {
             error!( target: "SCP",
-                "Resource limits exceeded -- Time elapsed: {} ms, Time limit: {} ms; Memory usage: {} bytes, Memory limit: {} bytes",
-                self.current_usage.time.as_millis(), self.limits.time.as_millis(), self.current_usage.mem_bytes, self.limits.mem_bytes
+                "Resource limits exceeded ({}) during {} phase -- Time elapsed: {} ms, Time limit: {} ms; Memory usage: {} bytes, Memory limit: {} bytes; Conflicts: {}, Propagations: {}, Clauses: {}, Work limits: {:?}",
+                resource, self.phase, self.current_usage.time.as_millis(), self.limits.time.as_millis(), self.current_usage.mem_bytes, self.limits.mem_bytes,
+                self.current_usage.conflicts, self.current_usage.propagations, self.current_usage.clauses, self.work_limits
             );
-            return Err(FbasError::ResourcelimitExceeded(self.current_usage));
+            return Err(FbasError::ResourcelimitExceeded(
+                self.current_usage,
+                resource,
+                self.phase,
+            ));
         }
         Ok(())
     }
+
+    fn at_checkpoint(&self) -> bool {
+        self.work_limits.max_propagations.is_some()
+            && self.current_usage.conflicts >= self.next_checkpoint
+    }
 }
 
 impl ResourceLimiter {
+    // WARNING: This function sets a global memory limit that affects the entire
+    // process, see `AllocatorMeter`.
     pub fn new(time_limit_ms: u64, global_mem_limit_bytes: usize) -> Self {
-        Self(Rc::new(RefCell::new(ResourceLimiterImpl::new(
+        Self::with_meters(
             time_limit_ms,
             global_mem_limit_bytes,
+            SystemClock::new(),
+            AllocatorMeter,
+        )
+    }
+
+    /// Creates a limiter that measures time with `clock` and memory usage with
+    /// `meter`, instead of the wall clock and the `LimitedAllocator`.
+    pub fn with_meters<C: Clock + 'static, M: MemoryMeter + 'static>(
+        time_limit_ms: u64,
+        mem_limit_bytes: usize,
+        clock: C,
+        meter: M,
+    ) -> Self {
+        Self(Rc::new(RefCell::new(ResourceLimiterImpl::new(
+            time_limit_ms,
+            mem_limit_bytes,
+            Rc::new(clock),
+            Rc::new(meter),
         ))))
     }
 
     #[cfg(test)]
     pub(crate) fn unlimited() -> Self {
-        Self(Rc::new(RefCell::new(ResourceLimiterImpl::new(
-            u64::MAX,
-            usize::MAX,
-        ))))
+        Self::new(u64::MAX, usize::MAX)
+    }
+
+    /// Makes the analysis cancellable through the handle obtained from
+    /// `interrupt.get_handle()`. Once interrupted, the analysis (parsing,
+    /// formula construction or solving) stops with `FbasError::Interrupted`.
+    pub fn with_interrupt(self, interrupt: AsyncInterrupt) -> Self {
+        self.0.borrow_mut().hooks.interrupt = Some(interrupt);
+        self
+    }
+
+    /// Registers a callback that is periodically invoked with the solver's
+    /// progress while solving.
+    pub fn with_progress_callback<F: FnMut(&SolveProgress) + 'static>(self, f: F) -> Self {
+        self.0.borrow_mut().hooks.on_progress = Some(Box::new(f));
+        self
+    }
+
+    /// Adds deterministic work budgets on top of the time and memory limits.
+    pub fn with_work_limits(self, work_limits: WorkLimits) -> Self {
+        self.0.borrow_mut().work_limits = work_limits;
+        self
+    }
+
+    /// Gives `phase` its own time and memory budget, on top of the overall
+    /// limits. The time limit applies to the time spent in the phase; the
+    /// memory limit applies to the memory in use while in the phase, and is
+    /// only checked when the limiter measures (not at allocation time). The
+    /// overall limits span the limiter's lifetime, parsing included, so a
+    /// phase limit is the way to bound one phase without taking time from
+    /// the others.
+    pub fn with_phase_limit(
+        self,
+        phase: Phase,
+        time_limit_ms: u64,
+        mem_limit_bytes: usize,
+    ) -> Self {
+        self.0.borrow_mut().phase_limits[phase.index()] =
+            Some(ResourceQuantity::new(time_limit_ms, mem_limit_bytes));
+        self
+    }
+
+    pub fn get_phase(&self) -> Phase {
+        self.0.borrow().phase
+    }
+
+    // Starts the budget of `phase`, restarting the phase time and the work
+    // counters. A limiter starts out in `Phase::Parse`.
+    pub(crate) fn enter_phase(&self, phase: Phase) {
+        let mut inner = self.0.borrow_mut();
+        inner.measure(false);
+        trace!(target: "SCP", "Entering {} phase after {} ms", phase, inner.current_usage.time.as_millis());
+        inner.phase = phase;
+        inner.phase_start_time = inner.current_usage.time;
+        inner.current_usage.conflicts = 0;
+        inner.current_usage.propagations = 0;
+        inner.current_usage.clauses = 0;
+        inner.next_checkpoint = PROPAGATION_CHECK_INTERVAL;
     }
 
     pub(crate) fn measure(&self, verbose: bool) {
@@ -124,6 +362,37 @@ impl ResourceLimiter {
     pub fn get_mem_bytes(&self) -> usize {
         self.0.borrow().current_usage.mem_bytes
     }
+
+    /// Returns the time and memory used so far, and the work done in the
+    /// current phase.
+    pub fn get_usage(&self) -> ResourceQuantity {
+        self.0.borrow().current_usage
+    }
+
+    /// Returns the highest memory usage seen by any measurement so far.
+    pub fn get_peak_mem_bytes(&self) -> usize {
+        self.0.borrow().peak_mem_bytes
+    }
+
+    pub(crate) fn add_clause(&self) {
+        self.0.borrow_mut().current_usage.clauses += 1;
+    }
+
+    pub(crate) fn add_propagations(&self, propagations: u64) {
+        self.0.borrow_mut().current_usage.propagations += propagations;
+    }
+
+    // Called when the solver stopped without a result. Returns true if it was
+    // paused at a propagation checkpoint with all limits still respected, in
+    // which case solving should be resumed.
+    pub(crate) fn resume_at_checkpoint(&self) -> bool {
+        let mut inner = self.0.borrow_mut();
+        if !inner.at_checkpoint() || inner.measure_and_enforce_limits().is_err() {
+            return false;
+        }
+        inner.next_checkpoint = inner.current_usage.conflicts + PROPAGATION_CHECK_INTERVAL;
+        true
+    }
 }
 
 impl Callbacks for ResourceLimiter {
@@ -157,6 +426,19 @@ impl Callbacks for ResourceLimiter {
         self.measure(false);
         let current = self.0.borrow().current_usage;
         let p = p();
+        // the callback is taken out for the duration of the call, so that it
+        // can query the limiter
+        let on_progress = self.0.borrow_mut().hooks.on_progress.take();
+        if let Some(mut on_progress) = on_progress {
+            on_progress(&SolveProgress {
+                conflicts: current.conflicts,
+                clauses: p.n_clauses,
+                learnt_clauses: p.n_learnt,
+                elapsed: current.time,
+                mem_bytes: current.mem_bytes,
+            });
+            self.0.borrow_mut().hooks.on_progress = Some(on_progress);
+        }
         trace!( target: "SCP",
             "c | {:9} | {:7} {:8} {:8} | {:8} {:8} {:6.0} | {:8} {:13} |",
             p.conflicts,
@@ -178,7 +460,14 @@ impl Callbacks for ResourceLimiter {
         );
     }
 
+    fn on_new_clause(&mut self, _: &[Lit], kind: ClauseKind) {
+        // every conflict produces exactly one learnt clause
+        if let ClauseKind::Learnt = kind {
+            self.0.borrow_mut().current_usage.conflicts += 1;
+        }
+    }
+
     fn stop(&self) -> bool {
-        self.measure_and_enforce_limits().is_err()
+        self.measure_and_enforce_limits().is_err() || self.0.borrow().at_checkpoint()
     }
 }
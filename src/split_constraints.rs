@@ -0,0 +1,105 @@
+use crate::{
+    cardinality::{at_least, at_most_assumption},
+    fbas::FbasError,
+    fbas_analyze::{solve_resumable, FbasAnalyzer, LimitedSolver, VarManager},
+    proof::AnalyzerCallbacks,
+    resource_limiter::Phase,
+};
+use batsat::Solver;
+use petgraph::graph::NodeIndex;
+
+// A constrained split search runs the analyzer's formula on a solver of its
+// own, so that neither the extra clauses nor an `UNSAT` result under the
+// constraints end up in the analyzer's formula or proof. Pinned and excluded
+// validators become assumptions on the quorum membership variables; the size
+// bound is a cardinality constraint over each quorum's validators.
+
+/// A pair of quorums, as lists of validators.
+pub type QuorumPair = (Vec<String>, Vec<String>);
+
+/// Constraints on the pair of disjoint quorums searched for by
+/// `FbasAnalyzer::find_constrained_split`.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SplitConstraints {
+    /// Validators that must be in quorum A.
+    pub in_quorum_a: Vec<String>,
+    /// Validators that must be in quorum B.
+    pub in_quorum_b: Vec<String>,
+    /// Validators that may be in neither quorum.
+    pub excluded: Vec<String>,
+    /// The most validators each quorum may have.
+    pub max_quorum_size: Option<usize>,
+}
+
+impl FbasAnalyzer {
+    /// Searches for a pair of disjoint quorums satisfying `constraints`, e.g.
+    /// with the validators of two organizations pinned to opposite quorums.
+    /// Returns `None` if there is none, which says nothing about splits that
+    /// violate the constraints. The search shares the analyzer's
+    /// `ResourceLimiter`, but not its solver.
+    pub fn find_constrained_split(
+        &self,
+        constraints: &SplitConstraints,
+    ) -> Result<Option<QuorumPair>, FbasError> {
+        let fbas = self.fbas();
+        let limiter = self.resource_limiter();
+        limiter.enter_phase(Phase::Formula);
+        let mut solver = Solver::new(Default::default(), AnalyzerCallbacks::new(limiter.clone()));
+        let mut sink = LimitedSolver(&mut solver);
+        let vars = VarManager::new(fbas, &mut sink);
+        Self::add_formula_clauses(fbas, &vars, &mut sink)?;
+
+        let mut assumptions = vec![];
+        for v in constraints.in_quorum_a.iter() {
+            assumptions.push(vars.lit_in_quorum_a(&fbas.validator_named(v)?, true)?);
+        }
+        for v in constraints.in_quorum_b.iter() {
+            assumptions.push(vars.lit_in_quorum_b(&fbas.validator_named(v)?, true)?);
+        }
+        for v in constraints.excluded.iter() {
+            let ni = fbas.validator_named(v)?;
+            assumptions.push(vars.lit_in_quorum_a(&ni, false)?);
+            assumptions.push(vars.lit_in_quorum_b(&ni, false)?);
+        }
+        if let Some(k) = constraints.max_quorum_size {
+            let quorum_a = fbas
+                .validators
+                .iter()
+                .map(|ni| vars.lit_in_quorum_a(ni, true))
+                .collect::<Result<Vec<_>, _>>()?;
+            let quorum_b = fbas
+                .validators
+                .iter()
+                .map(|ni| vars.lit_in_quorum_b(ni, true))
+                .collect::<Result<Vec<_>, _>>()?;
+            for lits in [quorum_a, quorum_b] {
+                let outputs = at_least(&mut sink, &lits, k + 1)?;
+                assumptions.extend(at_most_assumption(&outputs, k));
+            }
+        }
+
+        limiter.enter_phase(Phase::Solve);
+        let split = solve_resumable(&mut solver, &assumptions, |value| {
+            let (mut quorum_a, mut quorum_b) = (vec![], vec![]);
+            for ni in fbas.validators.iter() {
+                if vars.lit_in_quorum_a(ni, true).is_ok_and(value) {
+                    quorum_a.push(*ni);
+                }
+                if vars.lit_in_quorum_b(ni, true).is_ok_and(value) {
+                    quorum_b.push(*ni);
+                }
+            }
+            (quorum_a, quorum_b)
+        })?;
+        let Some((quorum_a, quorum_b)) = split else {
+            return Ok(None);
+        };
+        let names = |quorum: Vec<NodeIndex>| {
+            quorum
+                .iter()
+                .map(|ni| fbas.try_get_validator_string(ni))
+                .collect::<Result<Vec<_>, _>>()
+        };
+        Ok(Some((names(quorum_a)?, names(quorum_b)?)))
+    }
+}
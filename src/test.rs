@@ -1,5 +1,18 @@
 mod allocator;
 mod analyze;
+mod config;
+mod fault_sets;
+mod history;
+mod intersection;
 mod limits;
+mod lints;
+mod minimal_quorums;
+mod organizations;
 #[cfg(any(feature = "json", test))]
 mod parse;
+mod proof;
+mod quorums;
+mod split_constraints;
+mod tolerance;
+mod validation;
+mod write;
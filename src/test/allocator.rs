@@ -6,7 +6,7 @@ use std::process::Command;
 // below as a subprocess.
 #[ignore]
 #[test]
-fn test_allocator_limit_precision_ps() {
+fn test_allocator_limit_precision_ps() -> Result<(), Box<dyn std::error::Error>> {
     let args: Vec<String> = std::env::args().collect();
     let memory_limit: usize = if args.len() > 4 {
         args[4].parse().unwrap()
@@ -15,6 +15,20 @@ fn test_allocator_limit_precision_ps() {
     };
 
     set_memory_limit(memory_limit);
+
+    // growing an allocation counts the difference only, and fails beyond the
+    // limit without losing the allocation
+    let before = get_memory_usage();
+    let mut grown: Vec<u8> = Vec::with_capacity(1_000);
+    grown.try_reserve_exact(3_000)?;
+    assert_eq!(get_memory_usage() - before, grown.capacity());
+    assert!(grown.try_reserve_exact(memory_limit).is_err());
+    assert_eq!(get_memory_usage() - before, grown.capacity());
+    grown.shrink_to(500);
+    assert_eq!(get_memory_usage() - before, grown.capacity());
+    drop(grown);
+    assert_eq!(get_memory_usage(), before);
+
     let baseline = get_memory_usage();
 
     // Allocate chunks that fit within the limit minus baseline
@@ -41,6 +55,7 @@ fn test_allocator_limit_precision_ps() {
         used >= expected_min,
         "Memory usage {used} should be at least {expected_min} ({max_allocs}x{alloc_size})"
     );
+    Ok(())
 }
 
 #[test]
@@ -1,5 +1,9 @@
-use crate::{FbasAnalyzer, ResourceLimiter, SolveStatus};
-use std::collections::BTreeMap;
+use crate::{
+    FbasAnalyzer, FbasError, MissingNode, MissingNodePolicy, MissingReason, MockClock, MockMeter,
+    ParseOptions, ResourceLimiter, SolveStatus,
+};
+use std::{collections::BTreeMap, time::Duration};
+use stellar_xdr::curr::{Limits, NodeId, PublicKey, ScpQuorumSet, Uint256, WriteXdr};
 
 #[test]
 fn test() -> Result<(), Box<dyn std::error::Error>> {
@@ -13,6 +17,12 @@ fn test() -> Result<(), Box<dyn std::error::Error>> {
         ("homedomain_test_1", false),
         ("conflicted_3", true),
         ("conflicted", true),
+        ("insane_1", false),
+        ("lints_1", false),
+        ("diverging_organizations_1", false),
+        ("flat_organizations_1", true),
+        ("mixed_organizations_1", false),
+        ("stellar_core_quorum_1", false),
     ]);
 
     for entry in std::fs::read_dir("./tests/test_data/")? {
@@ -353,3 +363,181 @@ fn test_random_data() -> Result<(), Box<dyn std::error::Error>> {
     }
     Ok(())
 }
+
+#[test]
+fn test_statistics() -> Result<(), Box<dyn std::error::Error>> {
+    let meter = MockMeter::new(0);
+    let limiter = ResourceLimiter::with_meters(
+        u64::MAX,
+        usize::MAX,
+        MockClock::with_step(Duration::from_millis(1)),
+        meter.clone(),
+    );
+    let mut solver = FbasAnalyzer::from_json_path("./tests/test_data/top_tier.json", limiter)?;
+    let stats = solver.get_statistics().clone();
+    assert_eq!(stats.validators, 23);
+    assert!(stats.graph_vertices > stats.validators);
+    assert!(stats.graph_edges > 0);
+    assert_eq!(
+        stats.cnf_vars,
+        2 * stats.graph_vertices as u64 + stats.tseitin_vars
+    );
+    assert!(stats.tseitin_vars > 0 && stats.cnf_clauses > 0);
+    assert!(stats.build_time > Duration::ZERO);
+    assert_eq!(stats.solve_time, Duration::ZERO);
+    assert_eq!(stats.conflicts, 0);
+
+    meter.set(4096);
+    assert_eq!(solver.solve()?, SolveStatus::UNSAT);
+    meter.set(0);
+    let stats = solver.get_statistics();
+    assert!(stats.conflicts > 0 && stats.decisions > 0 && stats.propagations > 0);
+    assert!(stats.solve_time > Duration::ZERO);
+    assert_eq!(stats.peak_mem_bytes, 4096);
+    Ok(())
+}
+
+#[test]
+fn test_missing_node_policies() -> Result<(), Box<dyn std::error::Error>> {
+    // PK1 and PK2 both depend on PK3, which is not in the map
+    let json_file = "./tests/test_data/missing_1.json";
+    let solve_with_policy = |policy| {
+        let options = ParseOptions {
+            unknown_validators: policy,
+            ..Default::default()
+        };
+        let mut solver = FbasAnalyzer::from_json_path_with_options(
+            json_file,
+            &options,
+            ResourceLimiter::unlimited(),
+        )?;
+        assert_eq!(
+            solver.get_missing_nodes(),
+            &[MissingNode {
+                validator: "PK3".to_string(),
+                reason: MissingReason::UnknownValidator,
+                policy,
+            }]
+        );
+        solver.solve()
+    };
+    assert_eq!(
+        solve_with_policy(MissingNodePolicy::Crashed)?,
+        SolveStatus::UNSAT
+    );
+    assert_eq!(
+        solve_with_policy(MissingNodePolicy::Cooperating)?,
+        SolveStatus::UNSAT
+    );
+    assert!(matches!(
+        solve_with_policy(MissingNodePolicy::Byzantine)?,
+        SolveStatus::SAT(_)
+    ));
+    match solve_with_policy(MissingNodePolicy::Fail) {
+        Err(FbasError::UnresolvedValidators(nodes)) => assert_eq!(nodes[0].validator, "PK3"),
+        res => panic!("expected unresolved validators, got {res:?}"),
+    }
+
+    // a node with an empty quorum set buffer is reported as well
+    let node = |i: u8| NodeId(PublicKey::PublicKeyTypeEd25519(Uint256([i; 32])));
+    let qset = ScpQuorumSet {
+        threshold: 1,
+        validators: vec![node(1), node(2)].try_into()?,
+        inner_sets: vec![].try_into()?,
+    };
+    let nodes = [
+        node(1).to_xdr(Limits::none())?,
+        node(2).to_xdr(Limits::none())?,
+    ];
+    let qsets = [qset.to_xdr(Limits::none())?, vec![]];
+    let solver = FbasAnalyzer::from_quorum_set_map_buf(
+        nodes.iter(),
+        qsets.iter(),
+        ResourceLimiter::unlimited(),
+    )?;
+    let missing = solver.get_missing_nodes();
+    assert_eq!(missing.len(), 1);
+    assert_eq!(missing[0].reason, MissingReason::MissingQuorumSet);
+    assert_eq!(missing[0].policy, MissingNodePolicy::Crashed);
+
+    let options = ParseOptions {
+        missing_quorum_sets: MissingNodePolicy::Fail,
+        ..Default::default()
+    };
+    assert!(matches!(
+        FbasAnalyzer::from_quorum_set_map_buf_with_options(
+            nodes.iter(),
+            qsets.iter(),
+            &options,
+            ResourceLimiter::unlimited()
+        ),
+        Err(FbasError::UnresolvedValidators(_))
+    ));
+    Ok(())
+}
+
+#[test]
+fn test_node_analyzer() -> Result<(), Box<dyn std::error::Error>> {
+    // two disjoint groups, bridged only by PKX trusting both
+    let mut solver = FbasAnalyzer::from_json_path(
+        "./tests/test_data/conflicted.json",
+        ResourceLimiter::unlimited(),
+    )?;
+    assert!(matches!(solver.solve()?, SolveStatus::SAT(_)));
+    assert!(solver.get_nodes_outside_closure().is_empty());
+
+    let mut node = solver.to_node_analyzer("PK11", ResourceLimiter::unlimited())?;
+    assert_eq!(node.get_statistics().validators, 3);
+    assert_eq!(
+        node.get_nodes_outside_closure(),
+        ["PK21", "PK22", "PK23", "PKX"]
+    );
+    assert!(matches!(node.solve()?, SolveStatus::UNSAT));
+
+    let mut node = solver.to_node_analyzer("PKX", ResourceLimiter::unlimited())?;
+    assert!(node.get_nodes_outside_closure().is_empty());
+    assert!(matches!(node.solve()?, SolveStatus::SAT(_)));
+
+    assert!(matches!(
+        solver.to_node_analyzer("PK31", ResourceLimiter::unlimited()),
+        Err(FbasError::UnknownValidator(_))
+    ));
+
+    // validators without a quorum set keep their reason and policy, even when
+    // crashed ones are not in the graph
+    let missing = "GDRTHCOC6K6GAT3LNO7L2PQZHM7E3JDUHHSNRKSTH53A2AHBM6WZOUOC";
+    let solver = FbasAnalyzer::from_json_path_with_options(
+        "./tests/test_data/stellar_core_quorum_1.json",
+        &ParseOptions {
+            missing_quorum_sets: MissingNodePolicy::Crashed,
+            unknown_validators: MissingNodePolicy::Fail,
+            ..Default::default()
+        },
+        ResourceLimiter::unlimited(),
+    )?;
+    let node = solver.to_node_analyzer(
+        "GARHWC6Y4WNGLKCAC7SCFFLEV5GKTKB2AHVIA6C7SU5WLJTDW5W3MPHX",
+        ResourceLimiter::unlimited(),
+    )?;
+    assert_eq!(node.get_missing_nodes(), solver.get_missing_nodes());
+    assert_eq!(node.get_missing_nodes()[0].validator, missing);
+
+    let solver = FbasAnalyzer::from_json_path_with_options(
+        "./tests/test_data/metadata/metadata_1.json",
+        &ParseOptions {
+            only_active: true,
+            ..Default::default()
+        },
+        ResourceLimiter::unlimited(),
+    )?;
+    let node = solver.to_node_analyzer("PK1", ResourceLimiter::unlimited())?;
+    assert_eq!(
+        node.get_missing_nodes(),
+        &[MissingNode {
+            validator: "PK4".to_string(),
+            reason: MissingReason::Filtered,
+            policy: MissingNodePolicy::Crashed,
+        }]
+    );
+    Ok(())
+}
@@ -0,0 +1,68 @@
+use crate::{
+    fbas::InternalScpQuorumSet, toml_parser::quorum_set_map_from_toml, FbasAnalyzer, FbasError,
+    ResourceLimiter, SolveStatus,
+};
+
+fn org(validators: &[&str]) -> InternalScpQuorumSet {
+    InternalScpQuorumSet {
+        threshold: (validators.len() / 2 + 1) as u32,
+        validators: validators.iter().map(|v| v.to_string()).collect(),
+        inner_sets: vec![],
+    }
+}
+
+const TIER1: &str = "./tests/test_data/toml/tier1.cfg";
+
+#[test]
+fn test_quorum_set_from_toml() {
+    let quorum_map = quorum_set_map_from_toml(&[("A1", TIER1)]).unwrap();
+
+    // three HIGH quality orgs with a 2f+1 threshold, plus the MEDIUM tier as a
+    // single entry
+    let expected = InternalScpQuorumSet {
+        threshold: 3,
+        validators: vec![],
+        inner_sets: vec![
+            org(&["A1", "A2", "A3"]),
+            org(&["B1", "B2", "B3"]),
+            org(&["C1", "C2", "C3"]),
+            InternalScpQuorumSet {
+                threshold: 1,
+                validators: vec![],
+                inner_sets: vec![org(&["D1", "D2"])],
+            },
+        ],
+    };
+    assert_eq!(*quorum_map["A1"], expected);
+
+    match quorum_set_map_from_toml(&[("A1", "./tests/test_data/toml/too_few_high.cfg")]) {
+        Err(FbasError::ParseError(_, location)) => {
+            assert_eq!(location.node.as_deref(), Some("A1"));
+            assert_eq!(location.json_pointer.as_deref(), Some("/VALIDATORS"));
+        }
+        res => panic!("expected a parse error, got {res:?}"),
+    }
+}
+
+#[test]
+fn test_analyze_toml_configs() -> Result<(), Box<dyn std::error::Error>> {
+    let nodes = [
+        "A1", "A2", "A3", "B1", "B2", "B3", "C1", "C2", "C3", "D1", "D2",
+    ];
+    let configs = nodes.iter().map(|n| (*n, TIER1)).collect::<Vec<_>>();
+    let mut solver = FbasAnalyzer::from_toml_paths(&configs, ResourceLimiter::unlimited())?;
+    assert_eq!(solver.solve()?, SolveStatus::UNSAT);
+
+    // two groups that each only trust themselves can diverge
+    let configs = [
+        ("A1", "./tests/test_data/toml/only_a.cfg"),
+        ("A2", "./tests/test_data/toml/only_a.cfg"),
+        ("A3", "./tests/test_data/toml/only_a.cfg"),
+        ("C1", "./tests/test_data/toml/only_c.cfg"),
+        ("C2", "./tests/test_data/toml/only_c.cfg"),
+        ("C3", "./tests/test_data/toml/only_c.cfg"),
+    ];
+    let mut solver = FbasAnalyzer::from_toml_paths(&configs, ResourceLimiter::unlimited())?;
+    assert!(matches!(solver.solve()?, SolveStatus::SAT(_)));
+    Ok(())
+}
@@ -0,0 +1,57 @@
+use crate::{FbasAnalyzer, OrganizationFaults, OrganizationGrouping, ResourceLimiter, SolveStatus};
+
+#[test]
+fn test_organization_fault_sets() -> Result<(), Box<dyn std::error::Error>> {
+    let solver = FbasAnalyzer::from_json_path(
+        "./tests/test_data/top_tier.json",
+        ResourceLimiter::unlimited(),
+    )?;
+    let organizations = solver.get_organizations(&OrganizationGrouping::HomeDomain);
+
+    // 7 organizations (six with a 2-of-3 inner threshold, lobstr.co with 3 of
+    // 5), 5 of which are needed: 3 crashed organizations halt the network,
+    // and two quorums share at least 3 organizations
+    let blocking =
+        solver.minimal_organization_blocking_set(&organizations, OrganizationFaults::Any)?;
+    assert_eq!(blocking.organizations.len(), 3);
+    let splitting = solver
+        .minimal_organization_splitting_set(&organizations, OrganizationFaults::Any)?
+        .unwrap();
+    assert_eq!(splitting.organizations.len(), 3);
+    for org in splitting.organizations.iter() {
+        assert!(organizations.validators_of(org).is_some());
+    }
+
+    // with a single faulty validator not counting against its organization,
+    // crashing it never blocks the organization, but being Byzantine lets it
+    // satisfy the organization on both sides of a split
+    let half = OrganizationFaults::Fraction(0.5);
+    let blocking = solver.minimal_organization_blocking_set(&organizations, half)?;
+    assert_eq!(blocking.organizations.len(), 3);
+    let splitting = solver
+        .minimal_organization_splitting_set(&organizations, half)?
+        .unwrap();
+    assert!(splitting.organizations.is_empty());
+    assert!(!splitting.validators.is_empty());
+    Ok(())
+}
+
+#[test]
+fn test_fault_sets_without_quorum_intersection() -> Result<(), Box<dyn std::error::Error>> {
+    let mut solver = FbasAnalyzer::from_json_path(
+        "./tests/test_data/random/almost_symmetric_network_12_orgs_delete_prob_factor_11.json",
+        ResourceLimiter::unlimited(),
+    )?;
+    assert!(matches!(solver.solve()?, SolveStatus::SAT(_)));
+    // each validator is its own organization
+    let organizations = solver.get_organizations(&OrganizationGrouping::OrganizationId);
+    let splitting = solver
+        .minimal_organization_splitting_set(&organizations, OrganizationFaults::Any)?
+        .unwrap();
+    assert!(splitting.organizations.is_empty());
+    assert!(splitting.validators.is_empty());
+    let blocking =
+        solver.minimal_organization_blocking_set(&organizations, OrganizationFaults::Any)?;
+    assert!(!blocking.organizations.is_empty());
+    Ok(())
+}
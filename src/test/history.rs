@@ -0,0 +1,178 @@
+use crate::{
+    history::{quorum_set_hash, quorum_set_map_from_scp_history},
+    FbasAnalyzer, FbasError, MissingReason, ResourceLimiter, SolveStatus,
+};
+use std::{io::Write, path::PathBuf};
+use stellar_xdr::curr::{
+    Hash, LedgerScpMessages, Limits, NodeId, PublicKey, ScpBallot, ScpEnvelope, ScpHistoryEntry,
+    ScpHistoryEntryV0, ScpQuorumSet, ScpStatement, ScpStatementExternalize, ScpStatementPledges,
+    Signature, Uint256, Value, WriteXdr,
+};
+
+fn node(i: u8) -> NodeId {
+    NodeId(PublicKey::PublicKeyTypeEd25519(Uint256([i; 32])))
+}
+
+fn qset(threshold: u32, nodes: &[u8]) -> ScpQuorumSet {
+    ScpQuorumSet {
+        threshold,
+        validators: nodes
+            .iter()
+            .map(|i| node(*i))
+            .collect::<Vec<_>>()
+            .try_into()
+            .unwrap(),
+        inner_sets: vec![].try_into().unwrap(),
+    }
+}
+
+fn externalize(sender: u8, qset_hash: Hash) -> ScpEnvelope {
+    ScpEnvelope {
+        statement: ScpStatement {
+            node_id: node(sender),
+            slot_index: 0,
+            pledges: ScpStatementPledges::Externalize(ScpStatementExternalize {
+                commit: ScpBallot {
+                    counter: 1,
+                    value: Value(vec![].try_into().unwrap()),
+                },
+                n_h: 1,
+                commit_quorum_set_hash: qset_hash,
+            }),
+        },
+        signature: Signature(vec![].try_into().unwrap()),
+    }
+}
+
+fn entry(ledger: u32, qsets: Vec<ScpQuorumSet>, messages: Vec<ScpEnvelope>) -> ScpHistoryEntry {
+    ScpHistoryEntry::V0(ScpHistoryEntryV0 {
+        quorum_sets: qsets.try_into().unwrap(),
+        ledger_messages: LedgerScpMessages {
+            ledger_seq: ledger,
+            messages: messages.try_into().unwrap(),
+        },
+    })
+}
+
+// writes the entries with their record marks, as in a history archive
+fn write_history(name: &str, entries: &[ScpHistoryEntry], trailing: &[u8]) -> PathBuf {
+    let path = std::env::temp_dir().join(format!("{}-{name}.xdr", std::process::id()));
+    let mut file = std::fs::File::create(&path).unwrap();
+    for entry in entries {
+        let xdr = entry.to_xdr(Limits::none()).unwrap();
+        file.write_all(&(0x8000_0000 | xdr.len() as u32).to_be_bytes())
+            .unwrap();
+        file.write_all(&xdr).unwrap();
+    }
+    file.write_all(trailing).unwrap();
+    path
+}
+
+fn key(i: u8) -> String {
+    stellar_strkey::ed25519::PublicKey([i; 32]).to_string()
+}
+
+#[test]
+fn test_quorum_set_map_from_scp_history() {
+    let all = qset(3, &[1, 2, 3, 4]);
+    let pair = qset(2, &[1, 2]);
+    let unknown = Hash([7; 32]);
+    let entries = [
+        entry(
+            10,
+            vec![all.clone()],
+            vec![
+                externalize(1, quorum_set_hash(&all).unwrap()),
+                externalize(2, quorum_set_hash(&all).unwrap()),
+                externalize(3, quorum_set_hash(&all).unwrap()),
+            ],
+        ),
+        // node 1 changes its quorum set, node 4 refers to an unknown one
+        entry(
+            11,
+            vec![pair.clone()],
+            vec![
+                externalize(1, quorum_set_hash(&pair).unwrap()),
+                externalize(4, unknown),
+            ],
+        ),
+    ];
+    let path = write_history("history", &entries, &[]);
+    let paths = [path.to_str().unwrap()];
+
+    let (quorum_map, unresolved) =
+        quorum_set_map_from_scp_history(&paths, None, &ResourceLimiter::unlimited()).unwrap();
+    assert_eq!(quorum_map.len(), 3);
+    assert_eq!(*quorum_map[&key(1)], pair.clone().into());
+    assert_eq!(*quorum_map[&key(2)], all.clone().into());
+    assert_eq!(unresolved.into_iter().collect::<Vec<_>>(), vec![key(4)]);
+
+    // as of ledger 10, node 1 still uses the quorum set of all four nodes
+    let (quorum_map, unresolved) =
+        quorum_set_map_from_scp_history(&paths, Some(10), &ResourceLimiter::unlimited()).unwrap();
+    assert_eq!(*quorum_map[&key(1)], all.into());
+    assert!(unresolved.is_empty());
+
+    let mut solver =
+        FbasAnalyzer::from_scp_history_paths(&paths, Some(10), ResourceLimiter::unlimited())
+            .unwrap();
+    assert_eq!(solver.solve().unwrap(), SolveStatus::UNSAT);
+    let solver =
+        FbasAnalyzer::from_scp_history_paths(&paths, None, ResourceLimiter::unlimited()).unwrap();
+    let missing = solver.get_missing_nodes();
+    assert!(missing
+        .iter()
+        .any(|m| m.validator == key(4) && m.reason == MissingReason::MissingQuorumSet));
+    std::fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn test_scp_history_decoding_error_location() {
+    let all = qset(1, &[1]);
+    let entries = [entry(
+        10,
+        vec![all.clone()],
+        vec![externalize(1, quorum_set_hash(&all).unwrap())],
+    )];
+    // a truncated second record
+    let path = write_history("truncated", &entries, &[0x80, 0, 0, 8, 0, 0]);
+    let paths = [path.to_str().unwrap()];
+    match quorum_set_map_from_scp_history(&paths, None, &ResourceLimiter::unlimited()) {
+        Err(FbasError::XdrDecodingError(_, location, _)) => {
+            assert_eq!(location.file.as_deref(), paths.first().copied());
+            assert_eq!(location.buffer_index, Some(1));
+        }
+        res => panic!("expected a decoding error, got {res:?}"),
+    }
+    std::fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn test_scp_history_forged_length() {
+    let all = qset(1, &[1]);
+    let mut message = externalize(1, quorum_set_hash(&all).unwrap());
+    if let ScpStatementPledges::Externalize(externalize) = &mut message.statement.pledges {
+        externalize.commit.value = Value(vec![0xab; 4].try_into().unwrap());
+    }
+    let xdr = entry(10, vec![all], vec![message])
+        .to_xdr(Limits::none())
+        .unwrap();
+    // a value claiming to be 2 GB long
+    let value = xdr
+        .windows(8)
+        .position(|w| w == [0, 0, 0, 4, 0xab, 0xab, 0xab, 0xab])
+        .unwrap();
+    let mut record = (0x8000_0000 | xdr.len() as u32).to_be_bytes().to_vec();
+    record.extend_from_slice(&xdr[..value]);
+    record.extend_from_slice(&0x7fff_fff0u32.to_be_bytes());
+    record.extend_from_slice(&xdr[value + 4..]);
+    let path = write_history("forged", &[], &record);
+    let paths = [path.to_str().unwrap()];
+    match quorum_set_map_from_scp_history(&paths, None, &ResourceLimiter::unlimited()) {
+        Err(FbasError::XdrDecodingError(_, location, _)) => {
+            assert_eq!(location.buffer_index, Some(0));
+        }
+        res => panic!("expected a decoding error, got {res:?}"),
+    }
+    std::fs::remove_file(path).unwrap();
+}
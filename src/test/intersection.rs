@@ -0,0 +1,39 @@
+use crate::{FbasAnalyzer, ResourceLimiter};
+
+#[test]
+fn test_minimum_quorum_intersection() -> Result<(), Box<dyn std::error::Error>> {
+    // two quorums of 5 of 7 organizations share 3 organizations, and slices
+    // of an organization share a validator
+    let solver = FbasAnalyzer::from_json_path(
+        "./tests/test_data/top_tier.json",
+        ResourceLimiter::unlimited(),
+    )?;
+    let minimum = solver.minimum_quorum_intersection()?.unwrap();
+    assert_eq!(minimum.intersection.len(), 3);
+    let (qa, qb) = &minimum.quorums;
+    assert!(solver.is_quorum(qa) && solver.is_quorum(qb));
+    let shared = qa
+        .iter()
+        .filter(|v| qb.contains(v))
+        .cloned()
+        .collect::<Vec<_>>();
+    assert_eq!(shared, minimum.intersection);
+
+    // disjoint quorums exist
+    let solver = FbasAnalyzer::from_json_path(
+        "./tests/test_data/conflicted.json",
+        ResourceLimiter::unlimited(),
+    )?;
+    let minimum = solver.minimum_quorum_intersection()?.unwrap();
+    assert!(minimum.intersection.is_empty());
+    let (qa, qb) = &minimum.quorums;
+    assert!(solver.is_quorum(qa) && solver.is_quorum(qb));
+
+    // everyone depends on a crashed validator
+    let solver = FbasAnalyzer::from_json_path(
+        "./tests/test_data/missing_1.json",
+        ResourceLimiter::unlimited(),
+    )?;
+    assert!(solver.minimum_quorum_intersection()?.is_none());
+    Ok(())
+}
@@ -1,13 +1,20 @@
-use crate::{FbasAnalyzer, FbasError, ResourceLimiter, SolveStatus};
+use crate::{
+    AsyncInterrupt, FbasAnalyzer, FbasError, MemoryMeter, MockClock, MockMeter, Phase,
+    ProcRssMeter, ResourceKind, ResourceLimiter, ResourceQuantity, SolveStatus, SplitConstraints,
+    WorkLimits,
+};
 use std::{
+    cell::RefCell,
     path::{Path, PathBuf},
     process::Command,
+    rc::Rc,
+    time::Duration,
 };
 
 fn assert_solver_limit_exceeded(res: Result<SolveStatus, FbasError>) -> bool {
     match res {
         Ok(_) => false,
-        Err(e) => matches!(e, FbasError::ResourcelimitExceeded(_)),
+        Err(e) => matches!(e, FbasError::ResourcelimitExceeded(..)),
     }
 }
 
@@ -153,3 +160,299 @@ fn test_resource_limiter_underflow() -> Result<(), Box<dyn std::error::Error>> {
     );
     Ok(())
 }
+
+#[test]
+fn test_time_limit_with_mock_clock() -> Result<(), Box<dyn std::error::Error>> {
+    let json_file =
+        "./tests/test_data/random/almost_symmetric_network_16_orgs_delete_prob_factor_3.json";
+    // every measurement advances the clock by 1 ms, so the limit trips after a
+    // fixed number of measurements regardless of the machine's speed
+    let clock = MockClock::with_step(Duration::from_millis(1));
+    let res = FbasAnalyzer::from_json_path(
+        json_file,
+        ResourceLimiter::with_meters(100, usize::MAX, clock.clone(), MockMeter::default()),
+    )
+    .and_then(|mut solver| solver.solve());
+    match res {
+        Err(FbasError::ResourcelimitExceeded(usage, ResourceKind::Time, Phase::Parse)) => {
+            assert_eq!(usage.time, Duration::from_millis(101));
+            assert_eq!(usage.mem_bytes, 0);
+        }
+        res => panic!("expected the time limit to be exceeded, got {res:?}"),
+    }
+
+    // the clock does not advance on its own, so a limit is never reached
+    let limiter =
+        ResourceLimiter::with_meters(1, usize::MAX, MockClock::new(), MockMeter::default());
+    let mut solver = FbasAnalyzer::from_json_path(json_file, limiter.clone())?;
+    assert_eq!(solver.solve()?, SolveStatus::UNSAT);
+    assert_eq!(limiter.get_time_ms(), 0);
+    Ok(())
+}
+
+#[test]
+fn test_memory_limit_with_mock_meter() -> Result<(), Box<dyn std::error::Error>> {
+    let meter = MockMeter::new(1_000);
+    let limiter = ResourceLimiter::with_meters(u64::MAX, 500, MockClock::new(), meter.clone());
+
+    // usage is measured relative to the usage when the limiter was created
+    meter.set(1_500);
+    limiter.measure_and_enforce_limits()?;
+    assert_eq!(limiter.get_mem_bytes(), 500);
+
+    meter.set(1_501);
+    assert!(matches!(
+        limiter.measure_and_enforce_limits(),
+        Err(FbasError::ResourcelimitExceeded(usage, ResourceKind::Memory, Phase::Parse)) if usage.mem_bytes == 501
+    ));
+
+    // memory dropping below the starting usage must not underflow
+    meter.set(0);
+    limiter.measure_and_enforce_limits()?;
+    assert_eq!(limiter.get_mem_bytes(), 0);
+
+    // exceeding the limit during parsing fails the analyzer construction
+    meter.set(10_000);
+    assert!(assert_solver_limit_exceeded(
+        FbasAnalyzer::from_json_path("./tests/test_data/circular_1.json", limiter)
+            .map(|_| SolveStatus::UNKNOWN)
+    ));
+    Ok(())
+}
+
+#[cfg(target_os = "linux")]
+#[test]
+fn test_proc_rss_meter() {
+    assert!(ProcRssMeter.usage_bytes() > 0);
+}
+
+fn solve_with_work_limits(
+    json_file: &str,
+    work_limits: WorkLimits,
+) -> Result<SolveStatus, FbasError> {
+    let limiter = ResourceLimiter::unlimited().with_work_limits(work_limits);
+    FbasAnalyzer::from_json_path(json_file, limiter)?.solve()
+}
+
+#[test]
+fn test_work_limits() -> Result<(), Box<dyn std::error::Error>> {
+    // enough conflicts for the solver to be paused at a propagation checkpoint
+    let json_file = "./tests/test_data/top_tier.json";
+    let limiter = ResourceLimiter::unlimited();
+    let mut solver = FbasAnalyzer::from_json_path(json_file, limiter.clone())?;
+    assert_eq!(solver.solve()?, SolveStatus::UNSAT);
+    // the clauses were counted in the formula phase, before solving
+    let usage = ResourceQuantity {
+        clauses: solver.get_statistics().cnf_clauses,
+        ..limiter.get_usage()
+    };
+    assert!(usage.conflicts > 0 && usage.propagations > 0 && usage.clauses > 0);
+
+    // budgets matching the actual work are not exceeded
+    let exact = WorkLimits {
+        max_conflicts: Some(usage.conflicts),
+        max_propagations: None,
+        max_clauses: Some(usage.clauses),
+    };
+    assert_eq!(
+        solve_with_work_limits(json_file, exact)?,
+        SolveStatus::UNSAT
+    );
+
+    // pausing the solver to check propagations must not change the verdict
+    let generous = WorkLimits {
+        max_propagations: Some(usage.propagations * 10),
+        ..Default::default()
+    };
+    assert_eq!(
+        solve_with_work_limits(json_file, generous)?,
+        SolveStatus::UNSAT
+    );
+
+    for (work_limits, expected) in [
+        (
+            WorkLimits {
+                max_conflicts: Some(usage.conflicts / 2),
+                ..Default::default()
+            },
+            ResourceKind::Conflicts,
+        ),
+        (
+            WorkLimits {
+                max_propagations: Some(usage.propagations / 2),
+                ..Default::default()
+            },
+            ResourceKind::Propagations,
+        ),
+        (
+            WorkLimits {
+                max_clauses: Some(usage.clauses / 2),
+                ..Default::default()
+            },
+            ResourceKind::Clauses,
+        ),
+    ] {
+        match solve_with_work_limits(json_file, work_limits) {
+            Err(FbasError::ResourcelimitExceeded(_, resource, _)) => assert_eq!(resource, expected),
+            res => panic!("expected {expected} budget to be exceeded, got {res:?}"),
+        }
+    }
+
+    // the outcome is reproducible: the same budget trips at the same point
+    let conflict_budget = WorkLimits {
+        max_conflicts: Some(usage.conflicts / 2),
+        ..Default::default()
+    };
+    let usages = (0..2)
+        .map(|_| {
+            let limiter = ResourceLimiter::unlimited().with_work_limits(conflict_budget);
+            let res = FbasAnalyzer::from_json_path(json_file, limiter.clone())?.solve();
+            assert!(res.is_err());
+            Ok((
+                limiter.get_usage().conflicts,
+                limiter.get_usage().propagations,
+            ))
+        })
+        .collect::<Result<Vec<_>, FbasError>>()?;
+    assert_eq!(usages[0], usages[1]);
+
+    // the budgets apply per phase, so a later query on the same limiter
+    // building the same formula again gets the full clause budget
+    let limiter = ResourceLimiter::unlimited().with_work_limits(WorkLimits {
+        max_clauses: Some(usage.clauses),
+        ..Default::default()
+    });
+    let mut solver = FbasAnalyzer::from_json_path(json_file, limiter.clone())?;
+    assert_eq!(solver.solve()?, SolveStatus::UNSAT);
+    assert_eq!(
+        solver.find_constrained_split(&SplitConstraints::default())?,
+        None
+    );
+    assert_eq!(solver.solve()?, SolveStatus::UNSAT);
+    Ok(())
+}
+
+#[test]
+fn test_work_limits_across_solver_runs() -> Result<(), Box<dyn std::error::Error>> {
+    let json_file = "./tests/test_data/top_tier.json";
+    let limiter = ResourceLimiter::unlimited();
+    let solver = FbasAnalyzer::from_json_path(json_file, limiter.clone())?;
+    let quorums = solver.enumerate_minimal_quorums(None, usize::MAX)?;
+    assert!(quorums.quorums.len() > 1);
+    let conflicts = limiter.get_usage().conflicts;
+    assert!(conflicts > 1);
+
+    // the enumeration runs the solver once per quorum, and the conflicts of
+    // all runs count against the budget of its solve phase
+    let limiter = ResourceLimiter::unlimited().with_work_limits(WorkLimits {
+        max_conflicts: Some(conflicts / 2),
+        ..Default::default()
+    });
+    let solver = FbasAnalyzer::from_json_path(json_file, limiter)?;
+    match solver.enumerate_minimal_quorums(None, usize::MAX) {
+        Err(FbasError::ResourcelimitExceeded(_, ResourceKind::Conflicts, Phase::Solve)) => {}
+        res => panic!("expected the conflict budget to be exceeded, got {res:?}"),
+    }
+    Ok(())
+}
+
+#[test]
+fn test_interrupt_and_progress() -> Result<(), Box<dyn std::error::Error>> {
+    let json_file = "./tests/test_data/top_tier.json";
+
+    // interrupting before the analysis starts cancels the parsing
+    let interrupt = AsyncInterrupt::default();
+    interrupt.get_handle().interrupt_async();
+    let limiter = ResourceLimiter::unlimited().with_interrupt(interrupt);
+    assert!(matches!(
+        FbasAnalyzer::from_json_path(json_file, limiter),
+        Err(FbasError::Interrupted)
+    ));
+
+    // progress is reported while solving
+    let progress = Rc::new(RefCell::new(vec![]));
+    let reported = progress.clone();
+    let limiter = ResourceLimiter::unlimited()
+        .with_progress_callback(move |p| reported.borrow_mut().push(*p));
+    let mut solver = FbasAnalyzer::from_json_path(json_file, limiter)?;
+    assert_eq!(solver.solve()?, SolveStatus::UNSAT);
+    let progress = progress.borrow();
+    assert!(!progress.is_empty());
+    assert!(progress.iter().all(|p| p.conflicts > 0 && p.clauses > 0));
+
+    // a handle can cancel the solve, e.g. from the progress callback
+    let interrupt = AsyncInterrupt::default();
+    let handle = interrupt.get_handle();
+    let limiter = ResourceLimiter::unlimited()
+        .with_interrupt(interrupt)
+        .with_progress_callback(move |_| handle.interrupt_async());
+    let mut solver = FbasAnalyzer::from_json_path(json_file, limiter)?;
+    assert!(matches!(solver.solve(), Err(FbasError::Interrupted)));
+    Ok(())
+}
+
+#[test]
+fn test_phase_limits() -> Result<(), Box<dyn std::error::Error>> {
+    let json_file = "./tests/test_data/top_tier.json";
+    let step_limiter = || {
+        ResourceLimiter::with_meters(
+            u64::MAX,
+            usize::MAX,
+            MockClock::with_step(Duration::from_millis(1)),
+            MockMeter::default(),
+        )
+    };
+
+    // a tight parse budget fails fast, and the error names the phase
+    match FbasAnalyzer::from_json_path(
+        json_file,
+        step_limiter().with_phase_limit(Phase::Parse, 5, usize::MAX),
+    ) {
+        Err(FbasError::ResourcelimitExceeded(usage, ResourceKind::Time, Phase::Parse)) => {
+            assert_eq!(usage.time, Duration::from_millis(6));
+        }
+        res => panic!(
+            "expected the parse budget to be exceeded, got {:?}",
+            res.map(|_| ())
+        ),
+    }
+
+    // the time spent building the formula does not count towards the solve budget
+    let limiter = step_limiter();
+    FbasAnalyzer::from_json_path(json_file, limiter.clone())?.solve()?;
+    let total_time = limiter.get_usage().time;
+    let build_limiter = step_limiter();
+    FbasAnalyzer::from_json_path(json_file, build_limiter.clone())?;
+    let build_time = build_limiter.get_usage().time;
+    let solve_budget = (total_time - build_time).as_millis() as u64 + 10;
+    assert!(solve_budget < total_time.as_millis() as u64);
+    let limiter = step_limiter().with_phase_limit(Phase::Solve, solve_budget, usize::MAX);
+    assert_eq!(
+        FbasAnalyzer::from_json_path(json_file, limiter.clone())?.solve()?,
+        SolveStatus::UNSAT
+    );
+    assert_eq!(limiter.get_phase(), Phase::Solve);
+
+    // a phase memory limit is checked against the memory in use, and only
+    // while in that phase
+    let meter = MockMeter::new(0);
+    let formula_mem_limiter = || {
+        ResourceLimiter::with_meters(u64::MAX, usize::MAX, MockClock::new(), meter.clone())
+            .with_phase_limit(Phase::Formula, u64::MAX, 100)
+    };
+    let mut solver = FbasAnalyzer::from_json_path(json_file, formula_mem_limiter())?;
+    meter.set(1_000);
+    assert_eq!(solver.solve()?, SolveStatus::UNSAT);
+    meter.set(0);
+    let limiter = formula_mem_limiter();
+    meter.set(101);
+    assert!(matches!(
+        FbasAnalyzer::from_json_path(json_file, limiter),
+        Err(FbasError::ResourcelimitExceeded(
+            _,
+            ResourceKind::Memory,
+            Phase::Formula
+        ))
+    ));
+    Ok(())
+}
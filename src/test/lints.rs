@@ -0,0 +1,53 @@
+use crate::{FbasAnalyzer, LintKind, OrganizationGrouping, ResourceLimiter, Severity};
+
+#[test]
+fn test_lints() -> Result<(), Box<dyn std::error::Error>> {
+    let solver = FbasAnalyzer::from_json_path(
+        "./tests/test_data/top_tier.json",
+        ResourceLimiter::unlimited(),
+    )?;
+    let organizations = solver.get_organizations(&OrganizationGrouping::HomeDomain);
+    assert!(solver.lint(&organizations).is_empty());
+
+    let solver = FbasAnalyzer::from_json_path(
+        "./tests/test_data/lints_1.json",
+        ResourceLimiter::unlimited(),
+    )?;
+    let organizations = solver.get_organizations(&OrganizationGrouping::HomeDomain);
+    let lints = solver.lint(&organizations);
+    let count = |kind: fn(&LintKind) -> bool| lints.iter().filter(|l| kind(&l.kind)).count();
+    // b.com has 2 validators, m.com 1
+    assert_eq!(
+        count(|k| matches!(k, LintKind::SmallOrganization { .. })),
+        3
+    );
+    // everyone but PKM has a 1-of-2 inner set
+    assert_eq!(
+        count(|k| matches!(k, LintKind::InnerThresholdOne { path, entries: 2 } if path == &[1])),
+        5
+    );
+    let of = |validator: &str| {
+        lints
+            .iter()
+            .filter(|l| l.validator == validator)
+            .map(|l| (l.severity, l.kind.clone()))
+            .collect::<Vec<_>>()
+    };
+    assert!(of("PKB1").contains(&(
+        Severity::Info,
+        LintKind::AsymmetricTrust {
+            trusted: "PKB2".to_string()
+        }
+    )));
+    let pkb2 = of("PKB2");
+    assert!(pkb2.contains(&(
+        Severity::Warning,
+        LintKind::UnknownValidator("PKX".to_string())
+    )));
+    assert!(pkb2.contains(&(Severity::Warning, LintKind::DivergingTopTier)));
+    // PKM is not trusted by anyone, so not part of the top tier
+    assert!(of("PKM").contains(&(Severity::Warning, LintKind::MissingSelf)));
+    assert_eq!(count(|k| matches!(k, LintKind::DivergingTopTier)), 1);
+    assert_eq!(count(|k| matches!(k, LintKind::AsymmetricTrust { .. })), 1);
+    Ok(())
+}
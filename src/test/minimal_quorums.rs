@@ -0,0 +1,57 @@
+use crate::{FbasAnalyzer, FbasError, ResourceLimiter};
+
+#[test]
+fn test_enumerate_minimal_quorums() -> Result<(), Box<dyn std::error::Error>> {
+    // two groups of 3 with 2-of-3 thresholds, and PKX trusting both
+    let solver = FbasAnalyzer::from_json_path(
+        "./tests/test_data/conflicted.json",
+        ResourceLimiter::unlimited(),
+    )?;
+    let all = solver.enumerate_minimal_quorums(None, 100)?;
+    assert!(!all.truncated);
+    assert_eq!(all.quorums.len(), 6);
+    for quorum in all.quorums.iter() {
+        assert_eq!(quorum.len(), 2);
+        assert!(solver.is_quorum(quorum));
+    }
+
+    let pk11 = solver.enumerate_minimal_quorums(Some("PK11"), 100)?;
+    assert_eq!(pk11.quorums.len(), 2);
+    assert!(pk11.quorums.iter().all(|q| q.contains(&"PK11".to_string())));
+    // PKX is in quorums, but never a minimal one
+    assert!(solver
+        .enumerate_minimal_quorums(Some("PKX"), 100)?
+        .quorums
+        .is_empty());
+
+    let limited = solver.enumerate_minimal_quorums(None, 6)?;
+    assert!(!limited.truncated);
+    let limited = solver.enumerate_minimal_quorums(None, 5)?;
+    assert!(limited.truncated);
+    assert_eq!(limited.quorums.len(), 5);
+
+    assert!(matches!(
+        solver.enumerate_minimal_quorums(Some("PK31"), 100),
+        Err(FbasError::UnknownValidator(_))
+    ));
+    Ok(())
+}
+
+#[test]
+fn test_enumerate_minimal_quorums_top_tier() -> Result<(), Box<dyn std::error::Error>> {
+    // 5 of 7 organizations, with many slices each
+    let solver = FbasAnalyzer::from_json_path(
+        "./tests/test_data/top_tier.json",
+        ResourceLimiter::unlimited(),
+    )?;
+    let sdf1 = "GCGB2S2KGYARPVIA37HYZXVRM2YZUEXA6S33ZU5BUDC6THSB62LZSTYH";
+    let some = solver.enumerate_minimal_quorums(Some(sdf1), 20)?;
+    assert!(some.truncated);
+    assert_eq!(some.quorums.len(), 20);
+    for quorum in some.quorums.iter() {
+        assert!(quorum.contains(&sdf1.to_string()));
+        assert!(solver.is_quorum(quorum));
+        assert!(quorum.len() == 10 || quorum.len() == 11);
+    }
+    Ok(())
+}
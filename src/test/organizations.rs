@@ -0,0 +1,170 @@
+use crate::{FbasAnalyzer, OrganizationGrouping, ResourceLimiter, SolveStatus};
+use std::collections::BTreeMap;
+
+// reads the `[{"name", "validators"}]` mappings next to the random networks
+fn organization_mapping(
+    path: &str,
+) -> Result<BTreeMap<String, String>, Box<dyn std::error::Error>> {
+    let orgs = json::parse(&std::fs::read_to_string(path)?)?;
+    let mut mapping = BTreeMap::new();
+    for org in orgs.members() {
+        for validator in org["validators"].members() {
+            mapping.insert(
+                validator.to_string(),
+                org["name"].as_str().ok_or("name missing")?.to_string(),
+            );
+        }
+    }
+    Ok(mapping)
+}
+
+#[test]
+fn test_organizations_by_home_domain() -> Result<(), Box<dyn std::error::Error>> {
+    let solver = FbasAnalyzer::from_json_path(
+        "./tests/test_data/top_tier.json",
+        ResourceLimiter::unlimited(),
+    )?;
+    let organizations = solver.get_organizations(&OrganizationGrouping::HomeDomain);
+    assert_eq!(organizations.len(), 7);
+    assert_eq!(
+        organizations
+            .validators_of("www.stellar.org")
+            .map(|v| v.len()),
+        Some(3)
+    );
+    let sdf1 = "GCGB2S2KGYARPVIA37HYZXVRM2YZUEXA6S33ZU5BUDC6THSB62LZSTYH";
+    assert_eq!(organizations.organization_of(sdf1), Some("www.stellar.org"));
+    assert_eq!(
+        organizations.group(&[sdf1]),
+        vec!["www.stellar.org".to_string()]
+    );
+
+    let mut org_solver = solver.to_organization_analyzer(
+        &OrganizationGrouping::HomeDomain,
+        ResourceLimiter::unlimited(),
+    )?;
+    assert_eq!(org_solver.get_statistics().validators, 7);
+    assert_eq!(org_solver.solve()?, SolveStatus::UNSAT);
+
+    // grouping by stellarbeat organization id gives the same organizations
+    let by_id = solver.get_organizations(&OrganizationGrouping::OrganizationId);
+    assert_eq!(by_id.len(), 7);
+    assert_eq!(by_id.group(&[sdf1]).len(), 1);
+    Ok(())
+}
+
+#[test]
+fn test_organizations_mixed_quorum_set() -> Result<(), Box<dyn std::error::Error>> {
+    // every validator needs 2 of PKA1, PKA2 (a.com) and PKB1 (b.com), so any
+    // two quorums intersect; merging PKA1 and PKA2 into a.com must not let
+    // b.com satisfy the collapsed quorum set alone
+    let mut solver = FbasAnalyzer::from_json_path(
+        "./tests/test_data/mixed_organizations_1.json",
+        ResourceLimiter::unlimited(),
+    )?;
+    assert_eq!(solver.solve()?, SolveStatus::UNSAT);
+    let mut org_solver = solver.to_organization_analyzer(
+        &OrganizationGrouping::HomeDomain,
+        ResourceLimiter::unlimited(),
+    )?;
+    assert_eq!(org_solver.get_statistics().validators, 2);
+    assert_eq!(org_solver.solve()?, SolveStatus::UNSAT);
+    // a.com alone satisfies the original quorum set, but not the collapsed one
+    assert_eq!(
+        org_solver.get_approximate_organizations(),
+        ["a.com".to_string(), "b.com".to_string()]
+    );
+    Ok(())
+}
+
+#[test]
+fn test_organizations_diverging_quorum_sets() -> Result<(), Box<dyn std::error::Error>> {
+    // PKA1 and PKA2 only need a.com, but PKA3 also needs a validator of
+    // b.com, so a.com on its own is no quorum and every quorum contains b.com
+    let mut solver = FbasAnalyzer::from_json_path(
+        "./tests/test_data/diverging_organizations_1.json",
+        ResourceLimiter::unlimited(),
+    )?;
+    assert_eq!(solver.solve()?, SolveStatus::UNSAT);
+    let mut org_solver = solver.to_organization_analyzer(
+        &OrganizationGrouping::HomeDomain,
+        ResourceLimiter::unlimited(),
+    )?;
+    assert_eq!(org_solver.solve()?, SolveStatus::UNSAT);
+    assert!(org_solver.get_approximate_organizations().is_empty());
+    Ok(())
+}
+
+#[test]
+fn test_organizations_flat_quorum_sets() -> Result<(), Box<dyn std::error::Error>> {
+    // PKA1..PKA3 and PKB1..PKB3 each need 2 of their own group, so both
+    // groups are disjoint quorums; the collapsed quorum sets must still be
+    // satisfied by a single organization
+    let mut solver = FbasAnalyzer::from_json_path(
+        "./tests/test_data/flat_organizations_1.json",
+        ResourceLimiter::unlimited(),
+    )?;
+    let mapping = ["A", "B"]
+        .iter()
+        .flat_map(|org| (1..=3).map(move |i| (format!("PK{org}{i}"), org.to_string())))
+        .collect();
+    let mut org_solver = solver.to_organization_analyzer(
+        &OrganizationGrouping::Mapping(mapping),
+        ResourceLimiter::unlimited(),
+    )?;
+    assert_eq!(org_solver.get_statistics().validators, 2);
+    assert!(matches!(solver.solve()?, SolveStatus::SAT(_)));
+    assert!(matches!(org_solver.solve()?, SolveStatus::SAT(_)));
+    assert!(org_solver.get_approximate_organizations().is_empty());
+    Ok(())
+}
+
+#[test]
+fn test_organizations_by_mapping() -> Result<(), Box<dyn std::error::Error>> {
+    for (network, orgs, expected_sat) in [
+        ("almost_symmetric_network_2_orgs_", "2_orgs", false),
+        ("almost_symmetric_network_10_orgs_", "10_orgs", false),
+        (
+            "almost_symmetric_network_12_orgs_delete_prob_factor_11",
+            "12_orgs",
+            true,
+        ),
+    ] {
+        let mapping = organization_mapping(&format!(
+            "./tests/test_data/random/for_stellar_core/almost_symmetric_network_{orgs}_orgs.json"
+        ))?;
+        let grouping = OrganizationGrouping::Mapping(mapping);
+        let mut solver = FbasAnalyzer::from_json_path(
+            &format!("./tests/test_data/random/{network}.json"),
+            ResourceLimiter::unlimited(),
+        )?;
+        let mut org_solver =
+            solver.to_organization_analyzer(&grouping, ResourceLimiter::unlimited())?;
+        let organizations = solver.get_organizations(&grouping);
+        assert_eq!(
+            org_solver.get_statistics().validators,
+            organizations.len(),
+            "{network}"
+        );
+        let org_status = org_solver.solve()?;
+        assert_eq!(
+            matches!(org_status, SolveStatus::SAT(_)),
+            expected_sat,
+            "{network}"
+        );
+        assert_eq!(
+            matches!(solver.solve()?, SolveStatus::SAT(_)),
+            expected_sat,
+            "{network}"
+        );
+        if expected_sat {
+            // the split is reported in organizations
+            let (a, b) = org_solver.get_potential_split()?;
+            assert!(a
+                .iter()
+                .chain(b.iter())
+                .all(|org| organizations.validators_of(org).is_some()));
+        }
+    }
+    Ok(())
+}
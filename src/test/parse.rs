@@ -1,6 +1,12 @@
-use crate::json_parser::quorum_set_map_from_json;
+use crate::{
+    fbas::ErrorLocation,
+    json_parser::{quorum_set_map_and_metadata_from_json, quorum_set_map_from_json},
+    FbasAnalyzer, FbasError, MissingNode, MissingNodePolicy, MissingReason, NodeMetadata,
+    ParseOptions, ResourceLimiter, SolveStatus, StellarCoreQuorumInfo,
+};
 use std::str::FromStr;
 use stellar_strkey::ed25519::PublicKey as StrKeyPublicKey;
+use stellar_xdr::curr::{Limits, NodeId, PublicKey, ScpQuorumSet, Uint256, WriteXdr};
 
 #[test]
 fn test_parse_quorum_set_map_from_json() {
@@ -59,3 +65,222 @@ fn test_parse_quorum_set_map_from_stellarbeats_json() {
     let expected_validator = "GAAV2GCVFLNN522ORUYFV33E76VPC22E72S75AQ6MBR5V45Z5DWVPWEU";
     assert_eq!(&first_inner.validators[0], expected_validator);
 }
+
+#[test]
+fn test_parse_stellarbeats_metadata() -> Result<(), Box<dyn std::error::Error>> {
+    let path = "./tests/test_data/metadata/metadata_1.json";
+    let (quorum_map, metadata) = quorum_set_map_and_metadata_from_json(path)?;
+    assert_eq!(quorum_map.len(), 4);
+    assert_eq!(
+        metadata["PK1"],
+        NodeMetadata {
+            name: Some("Alpha 1".to_string()),
+            home_domain: Some("alpha.example".to_string()),
+            isp: Some("Hetzner Online GmbH".to_string()),
+            organization_id: Some("org-alpha".to_string()),
+            active: Some(true),
+            is_validating: Some(true),
+        }
+    );
+    assert_eq!(
+        metadata["PK1"].to_string(),
+        "Alpha 1 of alpha.example in Hetzner Online GmbH"
+    );
+    assert_eq!(metadata["PK4"].to_string(), "unnamed node");
+    // layouts without metadata
+    let (_, metadata) = quorum_set_map_and_metadata_from_json("./tests/test_data/circular_1.json")?;
+    assert!(metadata.is_empty());
+
+    let solver = FbasAnalyzer::from_json_path(path, ResourceLimiter::unlimited())?;
+    assert_eq!(
+        solver
+            .get_node_metadata("PK3")
+            .and_then(|m| m.is_validating),
+        Some(false)
+    );
+    assert!(solver.get_missing_nodes().is_empty());
+
+    // PK4 is inactive, and PK3 in addition is not validating
+    for (options, filtered) in [
+        (
+            ParseOptions {
+                only_active: true,
+                ..Default::default()
+            },
+            vec!["PK4"],
+        ),
+        (
+            ParseOptions {
+                only_active: true,
+                only_validating: true,
+                ..Default::default()
+            },
+            vec!["PK3", "PK4"],
+        ),
+    ] {
+        let mut solver = FbasAnalyzer::from_json_path_with_options(
+            path,
+            &options,
+            ResourceLimiter::unlimited(),
+        )?;
+        let missing = solver.get_missing_nodes();
+        assert!(missing.iter().all(|m| m.reason == MissingReason::Filtered));
+        assert_eq!(
+            missing
+                .iter()
+                .map(|m| m.validator.as_str())
+                .collect::<Vec<_>>(),
+            filtered
+        );
+        // filtered nodes still have their metadata
+        assert!(solver.get_node_metadata("PK4").is_some());
+        assert_eq!(solver.solve()?, SolveStatus::UNSAT);
+    }
+    Ok(())
+}
+
+#[test]
+fn test_parse_quorum_set_map_from_stellar_core_json() -> Result<(), Box<dyn std::error::Error>> {
+    let quorum_map =
+        quorum_set_map_from_json("./tests/test_data/stellar_core_quorum_1.json").unwrap();
+
+    // the local node's quorum set comes from the slot info, the missing node
+    // has no quorum set
+    assert_eq!(
+        quorum_map.keys().cloned().collect::<Vec<_>>(),
+        vec![
+            "GARHWC6Y4WNGLKCAC7SCFFLEV5GKTKB2AHVIA6C7SU5WLJTDW5W3MPHX",
+            "GCJIDPIMNOJU4PASPDEHKLQWG2KAM45NNAUEQVY33XMYGAMSYICOK4H4",
+            "GD6SZQV3WEJUH352NTVLKEV2JM2RH266VPEM7EH5QLLI7ZZAALMLNUVN",
+        ]
+    );
+    let local = &quorum_map["GARHWC6Y4WNGLKCAC7SCFFLEV5GKTKB2AHVIA6C7SU5WLJTDW5W3MPHX"];
+    assert_eq!(local.threshold, 3);
+    assert_eq!(local.validators.len(), 4);
+
+    let qset = &quorum_map["GCJIDPIMNOJU4PASPDEHKLQWG2KAM45NNAUEQVY33XMYGAMSYICOK4H4"];
+    assert_eq!(qset.threshold, 3);
+    assert_eq!(qset.validators.len(), 2);
+    assert_eq!(qset.inner_sets.len(), 1);
+    assert_eq!(qset.inner_sets[0].threshold, 1);
+
+    // stellar-core reports the quorum set as missing, so the node is not an
+    // unknown validator
+    let solver = FbasAnalyzer::from_json_path_with_options(
+        "./tests/test_data/stellar_core_quorum_1.json",
+        &ParseOptions {
+            unknown_validators: MissingNodePolicy::Fail,
+            ..Default::default()
+        },
+        ResourceLimiter::unlimited(),
+    )?;
+    assert_eq!(
+        solver.get_missing_nodes(),
+        &[MissingNode {
+            validator: "GDRTHCOC6K6GAT3LNO7L2PQZHM7E3JDUHHSNRKSTH53A2AHBM6WZOUOC".to_string(),
+            reason: MissingReason::MissingQuorumSet,
+            policy: MissingNodePolicy::Crashed,
+        }]
+    );
+    assert_eq!(
+        solver.get_stellar_core_quorum_info(),
+        Some(&StellarCoreQuorumInfo {
+            node: "GARHWC6Y4WNGLKCAC7SCFFLEV5GKTKB2AHVIA6C7SU5WLJTDW5W3MPHX".to_string(),
+            phase: Some("EXTERNALIZE".to_string()),
+            agree: Some(3),
+            disagree: None,
+            missing: vec!["GDRTHCOC6K6GAT3LNO7L2PQZHM7E3JDUHHSNRKSTH53A2AHBM6WZOUOC".to_string()],
+        })
+    );
+    let solver = FbasAnalyzer::from_json_path(
+        "./tests/test_data/top_tier.json",
+        ResourceLimiter::unlimited(),
+    )?;
+    assert_eq!(solver.get_stellar_core_quorum_info(), None);
+
+    let path = "./tests/test_data/malformed/stellar_core_bad_agree.json";
+    match quorum_set_map_from_json(path) {
+        Err(FbasError::ParseError(_, location)) => {
+            assert_eq!(location.json_pointer.as_deref(), Some("/qset/agree"));
+            assert_eq!(
+                location.node.as_deref(),
+                Some("GARHWC6Y4WNGLKCAC7SCFFLEV5GKTKB2AHVIA6C7SU5WLJTDW5W3MPHX")
+            );
+        }
+        res => panic!("expected a parse error, got {res:?}"),
+    }
+    Ok(())
+}
+
+#[test]
+fn test_parse_error_location() {
+    let path = "./tests/test_data/malformed/stellarbeat_bad_inner.json";
+    match quorum_set_map_from_json(path) {
+        Err(FbasError::ParseError(_, location)) => assert_eq!(
+            *location,
+            ErrorLocation {
+                file: Some(path.to_string()),
+                json_pointer: Some("/1/quorumSet/innerQuorumSets/0/validators/1".to_string()),
+                node: Some("PK2".to_string()),
+                qset_path: vec![0],
+                buffer_index: None,
+            }
+        ),
+        res => panic!("expected a parse error, got {res:?}"),
+    }
+
+    let path = "./tests/test_data/malformed/regular_bad_threshold.json";
+    let err = quorum_set_map_from_json(path).unwrap_err();
+    match &err {
+        FbasError::ParseError(_, location) => {
+            assert_eq!(
+                location.json_pointer.as_deref(),
+                Some("/nodes/0/qset/v/1/t")
+            );
+            assert_eq!(location.qset_path, vec![0]);
+        }
+        _ => panic!("expected a parse error, got {err:?}"),
+    }
+    assert!(err.to_string().contains(path));
+}
+
+#[test]
+fn test_xdr_decoding_error_location() {
+    let node = NodeId(PublicKey::PublicKeyTypeEd25519(Uint256([1; 32])))
+        .to_xdr(Limits::none())
+        .unwrap();
+    let qset = ScpQuorumSet {
+        threshold: 1,
+        validators: vec![].try_into().unwrap(),
+        inner_sets: vec![].try_into().unwrap(),
+    }
+    .to_xdr(Limits::none())
+    .unwrap();
+
+    let nodes = [node.clone(), node.clone(), vec![0; 3]];
+    let qsets = [qset.clone(), qset.clone(), qset.clone()];
+    match FbasAnalyzer::from_quorum_set_map_buf(
+        nodes.iter(),
+        qsets.iter(),
+        ResourceLimiter::unlimited(),
+    ) {
+        Err(FbasError::XdrDecodingError(_, location, _)) => {
+            assert_eq!(*location, ErrorLocation::buffer(2))
+        }
+        res => panic!("expected a decoding error, got {:?}", res.map(|_| ())),
+    }
+
+    let qsets = [qset.clone(), qset[..5].to_vec(), qset];
+    match FbasAnalyzer::from_quorum_set_map_buf(
+        nodes.iter(),
+        qsets.iter(),
+        ResourceLimiter::unlimited(),
+    ) {
+        Err(FbasError::XdrDecodingError(_, location, err)) => {
+            assert_eq!(location.buffer_index, Some(1));
+            assert!(location.node.is_some());
+            assert!(matches!(err, stellar_xdr::curr::Error::Io(_)));
+        }
+        res => panic!("expected a decoding error, got {:?}", res.map(|_| ())),
+    }
+}
@@ -0,0 +1,142 @@
+use crate::{FbasAnalyzer, FbasError, ResourceLimiter, SolveStatus};
+
+fn parse_clauses(text: &str) -> Vec<(bool, Vec<i64>)> {
+    let mut clauses = vec![];
+    let mut current = vec![];
+    let mut deletion = false;
+    for token in text.split_whitespace() {
+        match token {
+            "d" => deletion = true,
+            "0" => {
+                clauses.push((deletion, std::mem::take(&mut current)));
+                deletion = false;
+            }
+            lit => current.push(lit.parse::<i64>().unwrap()),
+        }
+    }
+    assert!(current.is_empty(), "clause is not terminated by 0");
+    clauses
+}
+
+// Returns true if asserting the negation of `lemma` and unit propagating over
+// `clauses` leads to a conflict, i.e. `lemma` is a RUP clause.
+fn has_rup(num_vars: usize, clauses: &[Vec<i64>], lemma: &[i64]) -> bool {
+    let mut assignment = vec![0i8; num_vars + 1];
+    let value =
+        |assignment: &[i8], lit: i64| assignment[lit.unsigned_abs() as usize] * lit.signum() as i8;
+    for lit in lemma {
+        if value(&assignment, *lit) == 1 {
+            // the lemma is a tautology
+            return true;
+        }
+        assignment[lit.unsigned_abs() as usize] = -lit.signum() as i8;
+    }
+    loop {
+        let mut changed = false;
+        for clause in clauses {
+            let mut unassigned = None;
+            let mut num_unassigned = 0;
+            let mut satisfied = false;
+            for lit in clause {
+                match value(&assignment, *lit) {
+                    1 => {
+                        satisfied = true;
+                        break;
+                    }
+                    0 => {
+                        num_unassigned += 1;
+                        unassigned = Some(*lit);
+                    }
+                    _ => {}
+                }
+            }
+            if satisfied {
+                continue;
+            }
+            match (num_unassigned, unassigned) {
+                (0, _) => return true,
+                (1, Some(lit)) => {
+                    assignment[lit.unsigned_abs() as usize] = lit.signum() as i8;
+                    changed = true;
+                }
+                _ => {}
+            }
+        }
+        if !changed {
+            return false;
+        }
+    }
+}
+
+// A minimal forward DRUP checker. Deletions are ignored, which only makes the
+// clause database larger and therefore cannot make an invalid proof pass.
+fn check_drup(dimacs: &str, proof: &str) {
+    let header = dimacs.lines().next().unwrap();
+    let fields = header.split_whitespace().collect::<Vec<_>>();
+    assert_eq!(fields[..2], ["p", "cnf"]);
+    let num_vars: usize = fields[2].parse().unwrap();
+    let num_clauses: usize = fields[3].parse().unwrap();
+
+    let mut clauses = parse_clauses(&dimacs[header.len()..])
+        .into_iter()
+        .map(|(_, c)| c)
+        .collect::<Vec<_>>();
+    assert_eq!(clauses.len(), num_clauses);
+
+    let lemmas = parse_clauses(proof);
+    // the proof must terminate with the empty clause
+    assert_eq!(lemmas.last(), Some(&(false, vec![])));
+    for (deletion, lemma) in lemmas {
+        if deletion {
+            continue;
+        }
+        assert!(
+            has_rup(num_vars, &clauses, &lemma),
+            "lemma {lemma:?} is not RUP"
+        );
+        clauses.push(lemma);
+    }
+}
+
+#[test]
+fn test_drat_proof_of_unsat() -> Result<(), Box<dyn std::error::Error>> {
+    for case in [
+        "./tests/test_data/circular_1.json",
+        "./tests/test_data/missing_1.json",
+        "./tests/test_data/top_tier.json",
+        "./tests/test_data/random/almost_symmetric_network_5_orgs_delete_prob_factor_3.json",
+    ] {
+        let mut solver = FbasAnalyzer::from_json_path(case, ResourceLimiter::unlimited())?;
+        solver.enable_proof_logging();
+        assert_eq!(solver.solve()?, SolveStatus::UNSAT);
+        check_drup(&solver.get_dimacs_formula()?, &solver.get_drat_proof()?);
+    }
+    Ok(())
+}
+
+#[test]
+fn test_drat_proof_unavailable() -> Result<(), Box<dyn std::error::Error>> {
+    // no proof exists for a SAT result
+    let mut solver = FbasAnalyzer::from_json_path(
+        "./tests/test_data/conflicted.json",
+        ResourceLimiter::unlimited(),
+    )?;
+    solver.enable_proof_logging();
+    assert!(matches!(solver.solve()?, SolveStatus::SAT(_)));
+    assert!(matches!(
+        solver.get_drat_proof(),
+        Err(FbasError::ProofUnavailable(_))
+    ));
+
+    // proof logging must be enabled before solving
+    let mut solver = FbasAnalyzer::from_json_path(
+        "./tests/test_data/circular_1.json",
+        ResourceLimiter::unlimited(),
+    )?;
+    assert_eq!(solver.solve()?, SolveStatus::UNSAT);
+    assert!(matches!(
+        solver.get_drat_proof(),
+        Err(FbasError::ProofUnavailable(_))
+    ));
+    Ok(())
+}
@@ -0,0 +1,80 @@
+use crate::{FbasAnalyzer, FbasError, OrganizationGrouping, ResourceLimiter};
+
+#[test]
+fn test_quorum_within() -> Result<(), Box<dyn std::error::Error>> {
+    let solver = FbasAnalyzer::from_json_path(
+        "./tests/test_data/top_tier.json",
+        ResourceLimiter::unlimited(),
+    )?;
+    let organizations = solver.get_organizations(&OrganizationGrouping::HomeDomain);
+    let orgs = organizations
+        .iter()
+        .filter(|(_, validators)| validators.len() == 3)
+        .map(|(_, validators)| validators)
+        .collect::<Vec<_>>();
+    assert_eq!(orgs.len(), 6);
+
+    // 5 of 7 organizations, 2 of 3 validators in each of them form a quorum
+    let mut live = orgs[..5]
+        .iter()
+        .flat_map(|validators| validators[..2].iter().cloned())
+        .collect::<Vec<_>>();
+    live.push("not a validator".to_string());
+    assert!(solver.quorum_exists_within(&live));
+    let quorum = solver.greatest_quorum_within(&live)?;
+    assert_eq!(quorum.len(), 10);
+    assert!(quorum.iter().all(|v| live.contains(v)));
+
+    // one of them down leaves only 4 organizations
+    live.remove(0);
+    assert!(!solver.quorum_exists_within(&live));
+    assert!(solver.greatest_quorum_within(&live)?.is_empty());
+
+    let all = organizations
+        .iter()
+        .flat_map(|(_, validators)| validators.iter())
+        .collect::<Vec<_>>();
+    assert_eq!(solver.greatest_quorum_within(&all)?.len(), 23);
+    assert!(!solver.quorum_exists_within::<&str>(&[]));
+    Ok(())
+}
+
+#[test]
+fn test_quorum_queries() -> Result<(), Box<dyn std::error::Error>> {
+    let solver = FbasAnalyzer::from_json_path(
+        "./tests/test_data/top_tier.json",
+        ResourceLimiter::unlimited(),
+    )?;
+    let sdf1 = "GCGB2S2KGYARPVIA37HYZXVRM2YZUEXA6S33ZU5BUDC6THSB62LZSTYH";
+    let organizations = solver.get_organizations(&OrganizationGrouping::HomeDomain);
+    let orgs = organizations
+        .iter()
+        .filter(|(_, validators)| validators.len() == 3)
+        .map(|(_, validators)| validators)
+        .collect::<Vec<_>>();
+    let pick = |orgs: &[&[String]], n: usize| {
+        orgs.iter()
+            .flat_map(|validators| validators[..n].iter().cloned())
+            .collect::<Vec<_>>()
+    };
+
+    let slice = pick(&orgs[..5], 2);
+    assert!(solver.is_quorum(&slice));
+    assert!(solver.is_quorum_slice(sdf1, &slice)?);
+    assert!(!solver.is_quorum(&slice[1..]));
+    assert!(!solver.is_quorum_slice(sdf1, &slice[1..])?);
+    // SDF 1 alone has no slice
+    assert!(!solver.is_quorum(&[sdf1]));
+
+    // 2 of 3 validators in 3 of 7 organizations block every slice
+    assert!(solver.is_v_blocking(sdf1, &pick(&orgs[..3], 2))?);
+    assert!(!solver.is_v_blocking(sdf1, &pick(&orgs[..2], 2))?);
+    assert!(!solver.is_v_blocking(sdf1, &pick(&orgs, 1))?);
+    assert!(!solver.is_v_blocking::<&str>(sdf1, &[])?);
+
+    assert!(matches!(
+        solver.is_quorum_slice("not a validator", &slice),
+        Err(FbasError::UnknownValidator(_))
+    ));
+    Ok(())
+}
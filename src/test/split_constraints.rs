@@ -0,0 +1,56 @@
+use crate::{FbasAnalyzer, FbasError, ResourceLimiter, SplitConstraints};
+
+#[test]
+fn test_constrained_split() -> Result<(), Box<dyn std::error::Error>> {
+    // two disjoint groups of 3, bridged only by PKX trusting both
+    let solver = FbasAnalyzer::from_json_path(
+        "./tests/test_data/conflicted.json",
+        ResourceLimiter::unlimited(),
+    )?;
+    let pinned = |a: &[&str], b: &[&str]| SplitConstraints {
+        in_quorum_a: a.iter().map(|v| v.to_string()).collect(),
+        in_quorum_b: b.iter().map(|v| v.to_string()).collect(),
+        ..Default::default()
+    };
+
+    let (qa, qb) = solver
+        .find_constrained_split(&pinned(&["PK11"], &["PK21"]))?
+        .unwrap();
+    assert!(qa.contains(&"PK11".to_string()));
+    assert!(qb.contains(&"PK21".to_string()));
+    assert!(qa.iter().all(|v| !qb.contains(v)));
+    // the same group can't be split
+    assert!(solver
+        .find_constrained_split(&pinned(&["PK11"], &["PK12"]))?
+        .is_none());
+
+    // without 2 of the first group, there is only the second one
+    let excluded = SplitConstraints {
+        excluded: vec!["PK11".to_string(), "PK12".to_string()],
+        ..Default::default()
+    };
+    assert!(solver.find_constrained_split(&excluded)?.is_none());
+
+    // the smallest quorums have 2 validators
+    let bounded = |k| SplitConstraints {
+        max_quorum_size: Some(k),
+        ..Default::default()
+    };
+    assert!(solver.find_constrained_split(&bounded(1))?.is_none());
+    let (qa, qb) = solver.find_constrained_split(&bounded(2))?.unwrap();
+    assert_eq!((qa.len(), qb.len()), (2, 2));
+
+    assert!(matches!(
+        solver.find_constrained_split(&pinned(&["PK31"], &[])),
+        Err(FbasError::UnknownValidator(_))
+    ));
+
+    let solver = FbasAnalyzer::from_json_path(
+        "./tests/test_data/top_tier.json",
+        ResourceLimiter::unlimited(),
+    )?;
+    assert!(solver
+        .find_constrained_split(&SplitConstraints::default())?
+        .is_none());
+    Ok(())
+}
@@ -0,0 +1,56 @@
+use crate::{FaultTolerance, FbasAnalyzer, ResourceLimiter};
+
+#[test]
+fn test_fault_tolerance() -> Result<(), Box<dyn std::error::Error>> {
+    let solver = FbasAnalyzer::from_json_path(
+        "./tests/test_data/top_tier.json",
+        ResourceLimiter::unlimited(),
+    )?;
+
+    // 5 of 7 organizations: six of 2 of 3 validators, and lobstr.co with
+    // 3 of 5 validators
+    let qsets = solver.get_quorum_set_fault_tolerance()?;
+    let org = qsets
+        .iter()
+        .find(|q| q.threshold == 2 && q.entries == 3)
+        .unwrap();
+    assert_eq!(org.validators.len(), 3);
+    assert_eq!(
+        org.entry_tolerance,
+        FaultTolerance {
+            crash: 2,
+            byzantine: 1
+        }
+    );
+    assert_eq!(org.entry_tolerance, org.validator_tolerance);
+    let top = qsets
+        .iter()
+        .find(|q| q.threshold == 5 && q.entries == 7)
+        .unwrap();
+    assert!(top.validators.is_empty());
+    assert_eq!(
+        top.entry_tolerance,
+        FaultTolerance {
+            crash: 3,
+            byzantine: 3
+        }
+    );
+
+    // blocking 3 organizations takes crashing 2 validators in each of the
+    // 2-of-3 ones (lobstr.co takes 3), while splitting them takes one
+    // Byzantine validator in each of any 3 organizations (2t - n = 1 for
+    // both 2 of 3 and 3 of 5)
+    let validators = solver.get_validator_fault_tolerance()?;
+    assert_eq!(
+        validators["GCGB2S2KGYARPVIA37HYZXVRM2YZUEXA6S33ZU5BUDC6THSB62LZSTYH"],
+        FaultTolerance {
+            crash: 6,
+            byzantine: 3
+        }
+    );
+    assert_eq!(
+        top.validator_tolerance,
+        validators["GCGB2S2KGYARPVIA37HYZXVRM2YZUEXA6S33ZU5BUDC6THSB62LZSTYH"]
+    );
+    Ok(())
+}
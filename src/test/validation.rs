@@ -0,0 +1,157 @@
+use crate::{
+    fbas::InternalScpQuorumSet, validation::validate_internal_quorum_set, FbasAnalyzer, FbasError,
+    ParseOptions, QuorumSetViolation, ResourceLimiter, SolveStatus, ViolationKind,
+};
+
+fn qset(
+    threshold: u32,
+    validators: &[&str],
+    inner_sets: Vec<InternalScpQuorumSet>,
+) -> InternalScpQuorumSet {
+    InternalScpQuorumSet {
+        threshold,
+        validators: validators.iter().map(|v| v.to_string()).collect(),
+        inner_sets,
+    }
+}
+
+fn kinds(violations: Vec<QuorumSetViolation>) -> Vec<(Vec<usize>, ViolationKind)> {
+    violations.into_iter().map(|v| (v.path, v.kind)).collect()
+}
+
+#[test]
+fn test_validate_quorum_set() {
+    let options = ParseOptions::default();
+
+    let sane = qset(2, &["A", "B"], vec![qset(1, &["C", "D"], vec![])]);
+    assert!(validate_internal_quorum_set("A", &sane, &options).is_empty());
+
+    // threshold bounds, reported with the path of the offending inner set
+    let insane = qset(
+        2,
+        &["A"],
+        vec![qset(0, &["B"], vec![]), qset(3, &["C", "D"], vec![])],
+    );
+    assert_eq!(
+        kinds(validate_internal_quorum_set("A", &insane, &options)),
+        vec![
+            (
+                vec![0],
+                ViolationKind::ThresholdOutOfBounds {
+                    threshold: 0,
+                    entries: 1
+                }
+            ),
+            (
+                vec![1],
+                ViolationKind::ThresholdOutOfBounds {
+                    threshold: 3,
+                    entries: 2
+                }
+            ),
+        ]
+    );
+
+    // duplicates across inner sets, and the self-in-qset convention
+    let dup = qset(1, &["B"], vec![qset(1, &["C", "B"], vec![])]);
+    let violations = validate_internal_quorum_set("A", &dup, &options);
+    assert!(violations.iter().all(|v| v.validator == "A"));
+    assert_eq!(
+        kinds(violations),
+        vec![
+            (vec![0], ViolationKind::DuplicateValidator("B".to_string())),
+            (vec![], ViolationKind::MissingSelf),
+        ]
+    );
+
+    // node count and depth limits are configurable
+    let options = ParseOptions {
+        max_depth: 2,
+        max_nodes: 2,
+        extra_checks: true,
+        ..Default::default()
+    };
+    let deep = qset(
+        1,
+        &["A", "B"],
+        vec![qset(2, &["C", "D"], vec![qset(1, &["E"], vec![])])],
+    );
+    assert_eq!(
+        kinds(validate_internal_quorum_set("A", &deep, &options)),
+        vec![
+            (
+                vec![],
+                ViolationKind::ThresholdBelowMajority {
+                    threshold: 1,
+                    entries: 3
+                }
+            ),
+            (vec![0, 0], ViolationKind::MaxDepthExceeded { depth: 2 }),
+            (vec![], ViolationKind::NodeCountOutOfBounds { count: 4 }),
+        ]
+    );
+}
+
+#[test]
+fn test_parse_options() -> Result<(), Box<dyn std::error::Error>> {
+    let json_file = "./tests/test_data/insane_1.json";
+
+    // by default violations are only reported
+    let mut solver = FbasAnalyzer::from_json_path(json_file, ResourceLimiter::unlimited())?;
+    assert_ne!(solver.solve()?, SolveStatus::UNKNOWN);
+    let reported = solver.get_quorum_set_violations().to_vec();
+    assert_eq!(reported.len(), 3);
+
+    let strict = ParseOptions {
+        strict: true,
+        ..Default::default()
+    };
+    match FbasAnalyzer::from_json_path_with_options(
+        json_file,
+        &strict,
+        ResourceLimiter::unlimited(),
+    ) {
+        Err(FbasError::InvalidQuorumSet(violations)) => {
+            assert_eq!(violations, reported);
+            let found = violations
+                .iter()
+                .map(|v| (v.validator.as_str(), v.path.clone(), v.kind.clone()))
+                .collect::<Vec<_>>();
+            assert_eq!(
+                found,
+                vec![
+                    (
+                        "PK1",
+                        vec![],
+                        ViolationKind::ThresholdOutOfBounds {
+                            threshold: 3,
+                            entries: 2
+                        }
+                    ),
+                    (
+                        "PK2",
+                        vec![0],
+                        ViolationKind::DuplicateValidator("PK2".to_string())
+                    ),
+                    ("PK3", vec![], ViolationKind::MissingSelf),
+                ]
+            );
+        }
+        res => panic!("expected invalid quorum sets, got {:?}", res.map(|_| ())),
+    }
+
+    // the depth limit applies when building the graph as well
+    let shallow = ParseOptions {
+        max_depth: 1,
+        ..Default::default()
+    };
+    assert!(matches!(
+        FbasAnalyzer::from_json_path_with_options(
+            "./tests/test_data/top_tier.json",
+            &shallow,
+            ResourceLimiter::unlimited()
+        ),
+        Err(FbasError::MaxDepthExceeded)
+    ));
+    Ok(())
+}
@@ -0,0 +1,91 @@
+use crate::{
+    fbas::Fbas, json_parser::quorum_set_map_and_metadata_from_json,
+    json_writer::quorum_set_map_to_json, FbasAnalyzer, FbasError, JsonLayout, MissingReason,
+    ParseOptions, ResourceLimiter, SolveStatus,
+};
+use stellar_xdr::curr::{Limits, NodeId, PublicKey, ScpQuorumSet, Uint256, WriteXdr};
+
+fn json_paths() -> Result<Vec<String>, Box<dyn std::error::Error>> {
+    let mut paths = vec![];
+    for dir in [
+        "./tests/test_data/",
+        "./tests/test_data/random/",
+        "./tests/test_data/random/for_stellar_core/",
+    ] {
+        for entry in std::fs::read_dir(dir)? {
+            let path = entry?.path();
+            let name = path.to_str().unwrap().to_string();
+            // skip organization mappings
+            if path.extension().is_some_and(|ext| ext == "json") && !name.ends_with("_orgs.json") {
+                paths.push(name);
+            }
+        }
+    }
+    Ok(paths)
+}
+
+#[test]
+fn test_json_round_trip() -> Result<(), Box<dyn std::error::Error>> {
+    let out = std::env::temp_dir().join(format!("{}-round-trip.json", std::process::id()));
+    let out = out.to_str().unwrap();
+    for path in json_paths()? {
+        let (quorum_map, metadata) = quorum_set_map_and_metadata_from_json(&path)?;
+        for layout in [JsonLayout::Regular, JsonLayout::Stellarbeat] {
+            std::fs::write(out, quorum_set_map_to_json(&quorum_map, &metadata, layout))?;
+            let (written_map, written_metadata) = quorum_set_map_and_metadata_from_json(out)?;
+            assert_eq!(written_map, quorum_map, "{path} in {layout:?} layout");
+            // only the stellarbeat layout carries metadata
+            if layout == JsonLayout::Stellarbeat {
+                assert_eq!(written_metadata, metadata, "{path} in {layout:?} layout");
+            }
+        }
+    }
+    std::fs::remove_file(out)?;
+    Ok(())
+}
+
+#[test]
+fn test_xdr_round_trip() -> Result<(), Box<dyn std::error::Error>> {
+    let options = ParseOptions::default();
+    let limiter = ResourceLimiter::unlimited();
+    let fbas = Fbas::from_json_path("./tests/test_data/top_tier.json", &options, &limiter)?;
+    let (nodes, quorum_sets) = fbas.to_quorum_set_map_buf()?;
+    let decoded =
+        Fbas::from_quorum_set_map_buf(nodes.iter(), quorum_sets.iter(), &options, &limiter)?;
+    assert_eq!(decoded.quorum_set_map, fbas.quorum_set_map);
+
+    let mut solver = FbasAnalyzer::from_quorum_set_map_buf(
+        nodes.iter(),
+        quorum_sets.iter(),
+        ResourceLimiter::unlimited(),
+    )?;
+    assert_eq!(solver.solve()?, SolveStatus::UNSAT);
+
+    // nodes without a quorum set are written with an empty buffer
+    let node = |i: u8| NodeId(PublicKey::PublicKeyTypeEd25519(Uint256([i; 32])));
+    let qset = ScpQuorumSet {
+        threshold: 1,
+        validators: vec![node(1), node(2)].try_into()?,
+        inner_sets: vec![].try_into()?,
+    };
+    let nodes = vec![
+        node(1).to_xdr(Limits::none())?,
+        node(2).to_xdr(Limits::none())?,
+    ];
+    let quorum_sets = vec![qset.to_xdr(Limits::none())?, vec![]];
+    let fbas = Fbas::from_quorum_set_map_buf(nodes.iter(), quorum_sets.iter(), &options, &limiter)?;
+    let (written_nodes, written_quorum_sets) = fbas.to_quorum_set_map_buf()?;
+    assert_eq!((written_nodes, written_quorum_sets), (nodes, quorum_sets));
+    assert_eq!(fbas.missing[0].reason, MissingReason::MissingQuorumSet);
+
+    // test networks with made-up validator names have no xdr representation
+    let solver = FbasAnalyzer::from_json_path(
+        "./tests/test_data/circular_1.json",
+        ResourceLimiter::unlimited(),
+    )?;
+    match solver.to_quorum_set_map_buf() {
+        Err(FbasError::ParseError(_, location)) => assert!(location.node.is_some()),
+        res => panic!("expected a parse error, got {res:?}"),
+    }
+    Ok(())
+}
@@ -0,0 +1,153 @@
+use crate::{
+    fbas::{Fbas, FbasError, Vertex},
+    fbas_analyze::FbasAnalyzer,
+};
+use petgraph::graph::NodeIndex;
+use std::collections::BTreeMap;
+
+// Local fault tolerance of quorum sets, computed from their thresholds alone.
+// A quorum set with `n` entries and threshold `t` is blocked once `n - t + 1`
+// entries crash, and two of its slices may be disjoint once `2t - n` entries
+// (the least they share) are Byzantine. Across nested quorum sets, an inner
+// set counts as one entry, which takes as many validators to fail as its own
+// tolerance. Validators appearing in several inner sets are counted in each,
+// so these numbers bound the tolerance from above; unlike the SAT analysis,
+// they say nothing about the network as a whole.
+
+/// How many faults a quorum set tolerates, either in entries (validators and
+/// inner sets) or in validators through nested quorum sets.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct FaultTolerance {
+    /// Crashes that block the quorum set (`n - t + 1` entries).
+    pub crash: usize,
+    /// Byzantine faults after which two slices may share no correct member
+    /// (`2t - n` entries, zero if slices can be disjoint already).
+    pub byzantine: usize,
+}
+
+/// The fault tolerance of a quorum set of the graph, in which validators
+/// without a known quorum set no longer count as entries.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct QuorumSetFaultTolerance {
+    pub threshold: u32,
+    pub entries: usize,
+    /// The validators listed directly, i.e. not in inner sets.
+    pub validators: Vec<String>,
+    pub entry_tolerance: FaultTolerance,
+    pub validator_tolerance: FaultTolerance,
+}
+
+impl Fbas {
+    // The tolerance of vertex `ni` in validators, where a validator is one.
+    fn validator_tolerance(
+        &self,
+        ni: NodeIndex,
+        memo: &mut BTreeMap<NodeIndex, FaultTolerance>,
+    ) -> FaultTolerance {
+        if let Some(tolerance) = memo.get(&ni) {
+            return *tolerance;
+        }
+        let tolerance = match self.graph.node_weight(ni) {
+            Some(Vertex::QSet(qset)) => {
+                let (crash, byzantine) =
+                    entry_tolerance(qset.threshold as usize, self.graph.neighbors(ni).count());
+                let mut children = self
+                    .graph
+                    .neighbors(ni)
+                    .map(|child| self.validator_tolerance(child, memo))
+                    .collect::<Vec<_>>();
+                children.sort_by_key(|t| t.crash);
+                let crash = children.iter().take(crash).map(|t| t.crash).sum();
+                children.sort_by_key(|t| t.byzantine);
+                let byzantine = children.iter().take(byzantine).map(|t| t.byzantine).sum();
+                FaultTolerance { crash, byzantine }
+            }
+            _ => FaultTolerance {
+                crash: 1,
+                byzantine: 1,
+            },
+        };
+        memo.insert(ni, tolerance);
+        tolerance
+    }
+
+    // Quorum sets with a zero threshold are assumed by a `MissingNodePolicy`
+    // and cannot fail, so they are left out.
+    pub(crate) fn quorum_set_fault_tolerance(
+        &self,
+    ) -> Result<Vec<QuorumSetFaultTolerance>, FbasError> {
+        let mut memo = BTreeMap::new();
+        let mut result = vec![];
+        for ni in self.graph.node_indices() {
+            let Some(Vertex::QSet(qset)) = self.graph.node_weight(ni) else {
+                continue;
+            };
+            if qset.threshold == 0 {
+                continue;
+            }
+            let entries = self.graph.neighbors(ni).count();
+            let (crash, byzantine) = entry_tolerance(qset.threshold as usize, entries);
+            result.push(QuorumSetFaultTolerance {
+                threshold: qset.threshold,
+                entries,
+                validators: qset
+                    .validators
+                    .iter()
+                    .map(|v| self.try_get_validator_string(v))
+                    .collect::<Result<_, _>>()?,
+                entry_tolerance: FaultTolerance { crash, byzantine },
+                validator_tolerance: self.validator_tolerance(ni, &mut memo),
+            });
+        }
+        Ok(result)
+    }
+
+    pub(crate) fn validator_fault_tolerance(
+        &self,
+    ) -> Result<BTreeMap<String, FaultTolerance>, FbasError> {
+        let mut memo = BTreeMap::new();
+        let mut result = BTreeMap::new();
+        for ni in self.validators.iter() {
+            if self.assumed.contains_key(ni) {
+                continue;
+            }
+            let Some(qset) = self.graph.neighbors(*ni).next() else {
+                continue;
+            };
+            result.insert(
+                self.try_get_validator_string(ni)?,
+                self.validator_tolerance(qset, &mut memo),
+            );
+        }
+        Ok(result)
+    }
+}
+
+// (n - t + 1, 2t - n) for `entries` n and `threshold` t, each at least zero.
+fn entry_tolerance(threshold: usize, entries: usize) -> (usize, usize) {
+    (
+        (entries + 1).saturating_sub(threshold),
+        (2 * threshold).saturating_sub(entries),
+    )
+}
+
+impl FbasAnalyzer {
+    /// Returns the fault tolerance of every quorum set in the graph, each
+    /// listed once even if shared by several validators. Weak inner sets,
+    /// e.g. an organization with threshold 1, show up as a Byzantine
+    /// tolerance of zero.
+    pub fn get_quorum_set_fault_tolerance(
+        &self,
+    ) -> Result<Vec<QuorumSetFaultTolerance>, FbasError> {
+        self.fbas().quorum_set_fault_tolerance()
+    }
+
+    /// Returns the fault tolerance of every validator's quorum set in
+    /// validators, aggregated through its inner sets. Validators whose quorum
+    /// set was assumed by a `MissingNodePolicy` are left out.
+    pub fn get_validator_fault_tolerance(
+        &self,
+    ) -> Result<BTreeMap<String, FaultTolerance>, FbasError> {
+        self.fbas().validator_fault_tolerance()
+    }
+}
@@ -0,0 +1,212 @@
+use crate::fbas::{ErrorLocation, FbasError, InternalScpQuorumSet, QuorumSetMap};
+use std::{collections::BTreeMap, rc::Rc};
+use toml::{Table, Value};
+
+// Synthesizes quorum sets the way stellar-core's automatic quorum set
+// configuration does (`Config::generateQuorumSet`):
+//
+// - the validators of each home domain (organization) form an inner set with a
+//   simple majority threshold,
+// - the organizations of the same quality form a quorum set, with a BFT
+//   threshold (`n - (n-1)/3`) for HIGH quality and above, and a simple
+//   majority below,
+// - the quorum set of the next lower quality is added as a single entry of the
+//   quorum set of the quality above it.
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+enum Quality {
+    Low,
+    Medium,
+    High,
+    Critical,
+}
+
+impl Quality {
+    fn parse(s: &str) -> Option<Self> {
+        match s {
+            "LOW" => Some(Quality::Low),
+            "MEDIUM" => Some(Quality::Medium),
+            "HIGH" => Some(Quality::High),
+            "CRITICAL" => Some(Quality::Critical),
+            _ => None,
+        }
+    }
+}
+
+struct Context<'a> {
+    file: &'a str,
+    node: &'a str,
+}
+
+impl Context<'_> {
+    fn error(&self, msg: &'static str, pointer: &str) -> FbasError {
+        FbasError::ParseError(
+            msg,
+            Box::new(ErrorLocation {
+                file: Some(self.file.to_string()),
+                json_pointer: Some(pointer.to_string()),
+                node: Some(self.node.to_string()),
+                ..Default::default()
+            }),
+        )
+    }
+}
+
+fn get_str<'a>(
+    table: &'a Table,
+    key: &str,
+    ctx: &Context,
+    pointer: &str,
+) -> Result<Option<&'a str>, FbasError> {
+    match table.get(key) {
+        None => Ok(None),
+        Some(Value::String(s)) => Ok(Some(s.as_str())),
+        Some(_) => Err(ctx.error("field is not a string", &format!("{pointer}/{key}"))),
+    }
+}
+
+fn get_array_of_tables<'a>(
+    root: &'a Table,
+    key: &str,
+    ctx: &Context,
+) -> Result<Vec<&'a Table>, FbasError> {
+    match root.get(key) {
+        None => Ok(vec![]),
+        Some(Value::Array(entries)) => entries
+            .iter()
+            .enumerate()
+            .map(|(i, entry)| {
+                entry
+                    .as_table()
+                    .ok_or_else(|| ctx.error("entry is not a table", &format!("/{key}/{i}")))
+            })
+            .collect(),
+        Some(_) => Err(ctx.error("field is not an array of tables", &format!("/{key}"))),
+    }
+}
+
+fn simple_majority(n: usize) -> u32 {
+    (n / 2 + 1) as u32
+}
+
+fn byzantine_threshold(n: usize) -> u32 {
+    (n - (n - 1) / 3) as u32
+}
+
+fn quorum_set_from_config(text: &str, ctx: &Context) -> Result<InternalScpQuorumSet, FbasError> {
+    let root = text
+        .parse::<Table>()
+        .map_err(|_| ctx.error("fail to parse to toml", ""))?;
+    if root.contains_key("QUORUM_SET") {
+        return Err(ctx.error(
+            "manual QUORUM_SET configuration is not supported",
+            "/QUORUM_SET",
+        ));
+    }
+
+    let mut domain_quality = BTreeMap::new();
+    for (i, entry) in get_array_of_tables(&root, "HOME_DOMAINS", ctx)?
+        .into_iter()
+        .enumerate()
+    {
+        let pointer = format!("/HOME_DOMAINS/{i}");
+        let domain = get_str(entry, "HOME_DOMAIN", ctx, &pointer)?
+            .ok_or_else(|| ctx.error("HOME_DOMAIN field missing", &pointer))?;
+        let quality = get_str(entry, "QUALITY", ctx, &pointer)?
+            .and_then(Quality::parse)
+            .ok_or_else(|| ctx.error("QUALITY field missing or invalid", &pointer))?;
+        if domain_quality.insert(domain, quality).is_some() {
+            return Err(ctx.error("duplicate HOME_DOMAIN", &pointer));
+        }
+    }
+
+    // quality -> home domain -> validators
+    let mut tiers: BTreeMap<Quality, BTreeMap<&str, Vec<String>>> = BTreeMap::new();
+    for (i, entry) in get_array_of_tables(&root, "VALIDATORS", ctx)?
+        .into_iter()
+        .enumerate()
+    {
+        let pointer = format!("/VALIDATORS/{i}");
+        let key = get_str(entry, "PUBLIC_KEY", ctx, &pointer)?
+            .ok_or_else(|| ctx.error("PUBLIC_KEY field missing", &pointer))?;
+        let domain = get_str(entry, "HOME_DOMAIN", ctx, &pointer)?
+            .ok_or_else(|| ctx.error("HOME_DOMAIN field missing", &pointer))?;
+        let quality = match get_str(entry, "QUALITY", ctx, &pointer)? {
+            Some(q) => {
+                let quality = Quality::parse(q).ok_or_else(|| {
+                    ctx.error("QUALITY field invalid", &format!("{pointer}/QUALITY"))
+                })?;
+                if domain_quality.get(domain).is_some_and(|&dq| dq != quality) {
+                    return Err(ctx.error(
+                        "QUALITY does not match the one of its HOME_DOMAIN",
+                        &format!("{pointer}/QUALITY"),
+                    ));
+                }
+                quality
+            }
+            None => *domain_quality.get(domain).ok_or_else(|| {
+                ctx.error("QUALITY missing and HOME_DOMAIN not configured", &pointer)
+            })?,
+        };
+        let validators = tiers.entry(quality).or_default().entry(domain).or_default();
+        if validators.iter().any(|v| v == key) {
+            return Err(ctx.error("duplicate PUBLIC_KEY", &format!("{pointer}/PUBLIC_KEY")));
+        }
+        validators.push(key.to_string());
+    }
+
+    for (quality, orgs) in tiers.iter() {
+        if *quality >= Quality::High && orgs.values().any(|validators| validators.len() < 3) {
+            return Err(ctx.error(
+                "HIGH and CRITICAL quality home domains require at least 3 validators",
+                "/VALIDATORS",
+            ));
+        }
+    }
+
+    // build from the lowest quality up, nesting each tier into the next
+    let mut qset: Option<InternalScpQuorumSet> = None;
+    for (quality, orgs) in tiers.into_iter() {
+        let mut inner_sets = orgs
+            .into_values()
+            .map(|validators| InternalScpQuorumSet {
+                threshold: simple_majority(validators.len()),
+                validators,
+                inner_sets: vec![],
+            })
+            .collect::<Vec<_>>();
+        inner_sets.extend(qset.take());
+        let threshold = if quality >= Quality::High {
+            byzantine_threshold(inner_sets.len())
+        } else {
+            simple_majority(inner_sets.len())
+        };
+        qset = Some(InternalScpQuorumSet {
+            threshold,
+            validators: vec![],
+            inner_sets,
+        });
+    }
+    qset.ok_or_else(|| ctx.error("no VALIDATORS configured", "/VALIDATORS"))
+}
+
+/// Builds the quorum set map from stellar-core configurations, given as pairs
+/// of the node's public key and the path of its configuration file. The
+/// public key has to be provided as the configuration only contains the
+/// node's secret seed.
+pub(crate) fn quorum_set_map_from_toml<S: AsRef<str>, P: AsRef<str>>(
+    configs: &[(S, P)],
+) -> Result<QuorumSetMap, FbasError> {
+    let mut quorum_map = QuorumSetMap::new();
+    for (node, path) in configs.iter() {
+        let ctx = Context {
+            file: path.as_ref(),
+            node: node.as_ref(),
+        };
+        let text = std::fs::read_to_string(path.as_ref())
+            .map_err(|_| ctx.error("fail to read file", ""))?;
+        let qset = quorum_set_from_config(&text, &ctx)?;
+        quorum_map.insert(node.as_ref().to_string(), Rc::new(qset));
+    }
+    Ok(quorum_map)
+}
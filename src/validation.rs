@@ -0,0 +1,156 @@
+use crate::fbas::{InternalScpQuorumSet, ParseOptions, QuorumSetMap};
+use std::collections::BTreeSet;
+use stellar_xdr::curr::ScpQuorumSet;
+
+/// The structural problems detected by the quorum set validation, mirroring
+/// stellar-core's `isQuorumSetSane`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ViolationKind {
+    /// The quorum set is nested deeper than `ParseOptions::max_depth`.
+    MaxDepthExceeded { depth: u32 },
+    /// The threshold is zero or larger than the number of entries.
+    ThresholdOutOfBounds { threshold: u32, entries: usize },
+    /// The threshold is not a strict majority of the entries (only checked
+    /// with `ParseOptions::extra_checks`).
+    ThresholdBelowMajority { threshold: u32, entries: usize },
+    /// The validator appears more than once in the quorum set.
+    DuplicateValidator(String),
+    /// The quorum set contains no validators, or more than
+    /// `ParseOptions::max_nodes`.
+    NodeCountOutOfBounds { count: usize },
+    /// The validator's quorum set does not contain the validator itself.
+    MissingSelf,
+}
+
+/// A violation found in the quorum set of `validator`. `path` lists the
+/// indices of the inner sets leading from the top-level quorum set to the
+/// offending one, i.e. it is empty for the top-level set.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct QuorumSetViolation {
+    pub validator: String,
+    pub path: Vec<usize>,
+    pub kind: ViolationKind,
+}
+
+impl std::fmt::Display for QuorumSetViolation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "quorum set of {} at {:?}: ", self.validator, self.path)?;
+        match &self.kind {
+            ViolationKind::MaxDepthExceeded { depth } => {
+                write!(f, "nesting depth {depth} exceeds the maximum")
+            }
+            ViolationKind::ThresholdOutOfBounds { threshold, entries } => {
+                write!(
+                    f,
+                    "threshold {threshold} is out of bounds for {entries} entries"
+                )
+            }
+            ViolationKind::ThresholdBelowMajority { threshold, entries } => {
+                write!(
+                    f,
+                    "threshold {threshold} is not a majority of {entries} entries"
+                )
+            }
+            ViolationKind::DuplicateValidator(v) => write!(f, "duplicate validator {v}"),
+            ViolationKind::NodeCountOutOfBounds { count } => {
+                write!(f, "node count {count} is out of bounds")
+            }
+            ViolationKind::MissingSelf => write!(f, "does not contain the validator itself"),
+        }
+    }
+}
+
+struct Checker<'a> {
+    options: &'a ParseOptions,
+    validator: &'a str,
+    known: BTreeSet<&'a str>,
+    count: usize,
+    violations: Vec<QuorumSetViolation>,
+}
+
+impl<'a> Checker<'a> {
+    fn report(&mut self, path: &[usize], kind: ViolationKind) {
+        self.violations.push(QuorumSetViolation {
+            validator: self.validator.to_string(),
+            path: path.to_vec(),
+            kind,
+        });
+    }
+
+    fn check(&mut self, qset: &'a InternalScpQuorumSet, path: &mut Vec<usize>) {
+        let depth = path.len() as u32;
+        if depth >= self.options.max_depth {
+            self.report(path, ViolationKind::MaxDepthExceeded { depth });
+            return;
+        }
+
+        let threshold = qset.threshold;
+        let entries = qset.validators.len() + qset.inner_sets.len();
+        if threshold < 1 || threshold as usize > entries {
+            self.report(
+                path,
+                ViolationKind::ThresholdOutOfBounds { threshold, entries },
+            );
+        } else if self.options.extra_checks && (threshold as usize) * 2 <= entries {
+            self.report(
+                path,
+                ViolationKind::ThresholdBelowMajority { threshold, entries },
+            );
+        }
+
+        for v in qset.validators.iter() {
+            self.count += 1;
+            if !self.known.insert(v.as_str()) {
+                self.report(path, ViolationKind::DuplicateValidator(v.clone()));
+            }
+        }
+
+        for (i, inner) in qset.inner_sets.iter().enumerate() {
+            path.push(i);
+            self.check(inner, path);
+            path.pop();
+        }
+    }
+}
+
+pub(crate) fn validate_internal_quorum_set(
+    validator: &str,
+    qset: &InternalScpQuorumSet,
+    options: &ParseOptions,
+) -> Vec<QuorumSetViolation> {
+    let mut checker = Checker {
+        options,
+        validator,
+        known: BTreeSet::new(),
+        count: 0,
+        violations: vec![],
+    };
+    checker.check(qset, &mut vec![]);
+    if checker.count < 1 || checker.count > options.max_nodes {
+        let count = checker.count;
+        checker.report(&[], ViolationKind::NodeCountOutOfBounds { count });
+    }
+    if !checker.known.contains(validator) {
+        checker.report(&[], ViolationKind::MissingSelf);
+    }
+    checker.violations
+}
+
+pub(crate) fn validate_quorum_set_map(
+    qsm: &QuorumSetMap,
+    options: &ParseOptions,
+) -> Vec<QuorumSetViolation> {
+    qsm.iter()
+        .flat_map(|(validator, qset)| validate_internal_quorum_set(validator, qset, options))
+        .collect()
+}
+
+/// Checks the quorum set declared by `validator` for structural problems.
+/// Returns all violations found, or an empty list if the quorum set is sane.
+pub fn validate_quorum_set(
+    validator: &str,
+    qset: &ScpQuorumSet,
+    options: &ParseOptions,
+) -> Vec<QuorumSetViolation> {
+    validate_internal_quorum_set(validator, &qset.clone().into(), options)
+}